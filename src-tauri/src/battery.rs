@@ -0,0 +1,264 @@
+use serde::Serialize;
+
+/// Mouse, keyboard or headset battery reported through the same interface
+#[derive(Debug, Clone, Serialize)]
+pub struct PeripheralBattery {
+    pub name: String,
+    pub percent: u8,
+}
+
+/// System battery state; every field is `None` on machines without one.
+#[derive(Debug, Clone, Default)]
+pub struct BatteryStats {
+    pub percent: Option<f32>,
+    /// Positive while charging, negative while discharging
+    pub power_w: Option<f32>,
+    pub on_ac: Option<bool>,
+    /// Until empty while discharging, until full while charging
+    pub minutes_remaining: Option<u32>,
+    pub peripherals: Vec<PeripheralBattery>,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /sys/class/power_supply/*
+// `type` is Battery, Mains or USB. Batteries with `scope` = Device belong to
+// peripherals (HID++, Bluetooth). Drivers report either energy_* (µWh) and
+// power_now (µW), or charge_* (µAh) and current_now (µA) plus voltage_now (µV).
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct BatteryMonitor {
+    root: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct SupplyReading {
+    capacity: Option<f32>,
+    energy_now_wh: Option<f64>,
+    energy_full_wh: Option<f64>,
+    power_w: Option<f64>,
+    status: String,
+}
+
+#[cfg(target_os = "linux")]
+impl BatteryMonitor {
+    pub fn new() -> Self {
+        Self::with_power_supply_root("/sys/class/power_supply")
+    }
+
+    /// Scans `<root>/*` instead of the live sysfs class.
+    pub fn with_power_supply_root(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn refresh(&mut self) -> BatteryStats {
+        let mut stats = BatteryStats::default();
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return stats;
+        };
+
+        let mut batteries = Vec::new();
+        let mut mains_seen = false;
+        let mut mains_online = false;
+
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let read = |attr: &str| std::fs::read_to_string(dir.join(attr)).ok().map(|s| s.trim().to_string());
+            let num = |attr: &str| read(attr).and_then(|s| s.parse::<f64>().ok());
+
+            match read("type").as_deref() {
+                Some("Mains") | Some("USB") => {
+                    mains_seen = true;
+                    mains_online |= num("online") == Some(1.0);
+                }
+                Some("Battery") => {
+                    if read("present").as_deref() == Some("0") {
+                        continue;
+                    }
+                    if read("scope").as_deref() == Some("Device") {
+                        if let Some(percent) = num("capacity") {
+                            let name = read("model_name")
+                                .filter(|n| !n.is_empty())
+                                .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+                            stats.peripherals.push(PeripheralBattery { name, percent: percent.clamp(0.0, 100.0) as u8 });
+                        }
+                        continue;
+                    }
+
+                    let voltage_v = num("voltage_now").map(|uv| uv / 1e6);
+                    let wh = |energy: &str, charge: &str| {
+                        num(energy)
+                            .map(|uwh| uwh / 1e6)
+                            .or_else(|| Some(num(charge)? / 1e6 * voltage_v?))
+                    };
+                    // Some drivers sign current/power by direction; status says that already
+                    let power_w = num("power_now")
+                        .map(|uw| uw.abs() / 1e6)
+                        .or_else(|| Some(num("current_now")?.abs() / 1e6 * voltage_v?));
+
+                    batteries.push(SupplyReading {
+                        capacity: num("capacity").map(|c| c as f32),
+                        energy_now_wh: wh("energy_now", "charge_now"),
+                        energy_full_wh: wh("energy_full", "charge_full"),
+                        power_w,
+                        status: read("status").unwrap_or_default(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        stats.peripherals.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if batteries.is_empty() {
+            return stats;
+        }
+
+        let charging = batteries.iter().any(|b| b.status == "Charging");
+        let discharging = batteries.iter().any(|b| b.status == "Discharging");
+        // Desktops with a UPS or a laptop without a Mains entry: infer from status
+        stats.on_ac = Some(if mains_seen { mains_online } else { !discharging });
+
+        // Sum energy across BAT0/BAT1 so dual-battery laptops show one figure
+        let now: Option<f64> = batteries.iter().map(|b| b.energy_now_wh).sum();
+        let full: Option<f64> = batteries.iter().map(|b| b.energy_full_wh).sum();
+        stats.percent = match (now, full) {
+            (Some(now), Some(full)) if full > 0.0 => Some((now / full * 100.0).min(100.0) as f32),
+            _ => {
+                let capacities: Vec<f32> = batteries.iter().filter_map(|b| b.capacity).collect();
+                (!capacities.is_empty()).then(|| capacities.iter().sum::<f32>() / capacities.len() as f32)
+            }
+        };
+
+        let power: Option<f64> = batteries.iter().map(|b| b.power_w).sum();
+        if let Some(power) = power {
+            let sign = if discharging { -1.0 } else if charging { 1.0 } else { 0.0 };
+            stats.power_w = Some((power * sign) as f32);
+
+            // Below ~0.1 W the estimate is meaningless (full, or idle at the charge threshold)
+            if power > 0.1 {
+                let hours = match (now, full) {
+                    (Some(now), _) if discharging => Some(now / power),
+                    (Some(now), Some(full)) if charging => Some((full - now).max(0.0) / power),
+                    _ => None,
+                };
+                stats.minutes_remaining = hours.map(|h| (h * 60.0) as u32);
+            }
+        }
+
+        stats
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Windows: GetSystemPowerStatus — no charge rate without IOCTL_BATTERY_*
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "windows")]
+pub struct BatteryMonitor;
+
+#[cfg(target_os = "windows")]
+impl BatteryMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> BatteryStats {
+        use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return BatteryStats::default();
+        }
+
+        // 128 = no system battery, 255 = status unknown
+        if status.BatteryFlag & 128 != 0 || status.BatteryFlag == 255 {
+            return BatteryStats::default();
+        }
+
+        BatteryStats {
+            percent: (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent as f32),
+            power_w: None,
+            on_ac: match status.ACLineStatus {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            },
+            // Only estimated while on battery; u32::MAX means unknown
+            minutes_remaining: (status.BatteryLifeTime != u32::MAX).then_some(status.BatteryLifeTime / 60),
+            peripherals: Vec::new(),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub struct BatteryMonitor;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl BatteryMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> BatteryStats {
+        BatteryStats::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    #[test]
+    fn discharging_battery_with_peripheral() {
+        let root = FixtureDir::new();
+        root.write("AC/type", "Mains\n")
+            .write("AC/online", "0\n")
+            .write("BAT0/type", "Battery\n")
+            .write("BAT0/present", "1\n")
+            .write("BAT0/status", "Discharging\n")
+            .write("BAT0/capacity", "49\n")
+            .write("BAT0/energy_now", "25000000\n")
+            .write("BAT0/energy_full", "50000000\n")
+            .write("BAT0/power_now", "10000000\n")
+            .write("hidpp_battery_0/type", "Battery\n")
+            .write("hidpp_battery_0/scope", "Device\n")
+            .write("hidpp_battery_0/capacity", "80\n")
+            .write("hidpp_battery_0/model_name", "MX Master 3\n");
+
+        let stats = BatteryMonitor::with_power_supply_root(root.path()).refresh();
+        // energy_now / energy_full wins over the rounded capacity file
+        assert_eq!(stats.percent, Some(50.0));
+        assert_eq!(stats.power_w, Some(-10.0));
+        assert_eq!(stats.on_ac, Some(false));
+        assert_eq!(stats.minutes_remaining, Some(150));
+        assert_eq!(stats.peripherals.len(), 1);
+        assert_eq!((stats.peripherals[0].name.as_str(), stats.peripherals[0].percent), ("MX Master 3", 80));
+    }
+
+    #[test]
+    fn charging_battery_reporting_charge_and_current() {
+        let root = FixtureDir::new();
+        root.write("BAT1/type", "Battery\n")
+            .write("BAT1/status", "Charging\n")
+            .write("BAT1/voltage_now", "10000000\n")
+            .write("BAT1/charge_now", "3000000\n")
+            .write("BAT1/charge_full", "4000000\n")
+            .write("BAT1/current_now", "-1000000\n");
+
+        let stats = BatteryMonitor::with_power_supply_root(root.path()).refresh();
+        assert_eq!(stats.percent, Some(75.0));
+        assert_eq!(stats.power_w, Some(10.0));
+        // No Mains entry: inferred from status
+        assert_eq!(stats.on_ac, Some(true));
+        assert_eq!(stats.minutes_remaining, Some(60));
+    }
+
+    #[test]
+    fn no_battery_leaves_fields_empty() {
+        let root = FixtureDir::new();
+        root.write("AC/type", "Mains\n").write("AC/online", "1\n");
+
+        let stats = BatteryMonitor::with_power_supply_root(root.path()).refresh();
+        assert!(stats.percent.is_none() && stats.on_ac.is_none() && stats.peripherals.is_empty());
+    }
+}
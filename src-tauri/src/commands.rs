@@ -1,41 +1,77 @@
-use crate::config::{self, AppConfig, ConfigState};
-use crate::stats::{StatsState, SystemStats};
-use tauri::{AppHandle, Emitter, Manager, State};
+use crate::config::{self, AppConfig, ConfigState, TrackedProcessConfig};
+use crate::metrics::MetricGroup;
+use crate::sampler::{MetricCatalog, StatsSnapshot, StatsState, StatsSubscriptions};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
 
-/// Notify the overlay that config changed (lightweight event, no heavy payload)
-fn notify_overlay(app: &AppHandle) {
-    let _ = app.emit_to("overlay", "config-updated", ());
+/// Bumped on every config change, so a window can tell which of two configs is newer
+static CONFIG_REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// Payload of the `config-updated` event and of `get_config`
+#[derive(Clone, Serialize)]
+pub struct ConfigUpdate {
+    revision: u64,
+    config: AppConfig,
+}
+
+/// Push the changed config to every window, so none of them has to poll for it.
+/// Called with the config lock held, so revisions follow the order of changes.
+fn notify_config(app: &AppHandle, cfg: &AppConfig) {
+    let revision = CONFIG_REVISION.fetch_add(1, Ordering::Relaxed) + 1;
+    let _ = app.emit("config-updated", ConfigUpdate { revision, config: cfg.clone() });
+}
+
+/// Current config with its revision; read under the same lock as every bump,
+/// so a window can order it against `config-updated` events.
+#[tauri::command]
+pub fn get_config(state: State<'_, ConfigState>) -> ConfigUpdate {
+    let cfg = state.0.lock().unwrap();
+    ConfigUpdate {
+        revision: CONFIG_REVISION.load(Ordering::Relaxed),
+        config: cfg.clone(),
+    }
 }
 
+/// Latest snapshot from the background sampler; never triggers a collection.
 #[tauri::command]
-pub fn get_config(state: State<'_, ConfigState>) -> AppConfig {
-    state.0.lock().unwrap().clone()
+pub fn get_stats(state: State<'_, StatsState>) -> StatsSnapshot {
+    StatsSnapshot::clone(&state.latest())
 }
 
+/// Have the calling window receive `stats-updated` events, at most one per
+/// `interval_ms` (0 = every snapshot). Subscribing again changes the rate.
+/// Returns the latest snapshot, so the window has something to show until the first event.
 #[tauri::command]
-pub fn get_stats(state: State<'_, StatsState>) -> SystemStats {
-    state.0.lock().unwrap().collect()
+pub fn subscribe_stats(
+    window: WebviewWindow,
+    subscriptions: State<'_, StatsSubscriptions>,
+    stats: State<'_, StatsState>,
+    interval_ms: u64,
+) -> StatsSnapshot {
+    subscriptions.subscribe(window.label(), Duration::from_millis(interval_ms));
+    StatsSnapshot::clone(&stats.latest())
+}
+
+#[tauri::command]
+pub fn unsubscribe_stats(window: WebviewWindow, subscriptions: State<'_, StatsSubscriptions>) {
+    subscriptions.unsubscribe(window.label());
+}
+
+#[tauri::command]
+pub fn get_metric_catalog(catalog: State<'_, MetricCatalog>) -> MetricCatalog {
+    catalog.inner().clone()
 }
 
 #[tauri::command]
 pub fn save_metric(app: AppHandle, state: State<'_, ConfigState>, key: String, enabled: bool) -> Result<bool, String> {
     let mut cfg = state.0.lock().unwrap();
-    match key.as_str() {
-        "cpu" => cfg.metrics.cpu = enabled,
-        "cpu_freq" => cfg.metrics.cpu_freq = enabled,
-        "ram" => cfg.metrics.ram = enabled,
-        "ram_gb" => cfg.metrics.ram_gb = enabled,
-        "gpu" => cfg.metrics.gpu = enabled,
-        "gpu_temp" => cfg.metrics.gpu_temp = enabled,
-        "gpu_power" => cfg.metrics.gpu_power = enabled,
-        "gpu_clock" => cfg.metrics.gpu_clock = enabled,
-        "vram" => cfg.metrics.vram = enabled,
-        "disk_io" => cfg.metrics.disk_io = enabled,
-        "net_io" => cfg.metrics.net_io = enabled,
-        _ => return Err(format!("Unknown metric: {}", key)),
+    if !cfg.metrics.set(&key, enabled) {
+        return Err(format!("Unknown metric: {}", key));
     }
     config::save_config(&cfg)?;
-    notify_overlay(&app);
+    notify_config(&app, &cfg);
     Ok(true)
 }
 
@@ -44,7 +80,179 @@ pub fn save_metrics_order(app: AppHandle, state: State<'_, ConfigState>, order:
     let mut cfg = state.0.lock().unwrap();
     cfg.metrics_order = order;
     config::save_config(&cfg)?;
-    notify_overlay(&app);
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn get_disk_devices() -> Vec<String> {
+    crate::stats::disk_devices()
+}
+
+#[tauri::command]
+pub fn save_disk_device(app: AppHandle, state: State<'_, ConfigState>, device: Option<String>) -> Result<bool, String> {
+    let mut cfg = state.0.lock().unwrap();
+    cfg.disk.device = device.filter(|d| !d.is_empty());
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+/// Returns the updated config, since adding or removing mounts changes the metric list
+#[tauri::command]
+pub fn save_filesystems(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<AppConfig, String> {
+    let mut cfg = state.0.lock().unwrap();
+    match key.as_str() {
+        "mounts" => {
+            let mut mounts: Vec<String> = Vec::new();
+            for mount in value.as_array().into_iter().flatten().filter_map(|v| v.as_str()) {
+                let mount = mount.trim().to_string();
+                if !mount.is_empty() && !mounts.contains(&mount) {
+                    mounts.push(mount);
+                }
+            }
+            cfg.set_filesystem_mounts(mounts);
+        }
+        "show_inodes" => cfg.filesystems.show_inodes = value.as_bool().unwrap_or(false),
+        _ => return Err(format!("Unknown filesystems key: {}", key)),
+    }
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(cfg.clone())
+}
+
+#[tauri::command]
+pub fn save_network(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<bool, String> {
+    let patterns = |value: &serde_json::Value| -> Vec<String> {
+        value
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut cfg = state.0.lock().unwrap();
+    match key.as_str() {
+        "include" => cfg.network.include = patterns(&value),
+        "exclude" => cfg.network.exclude = patterns(&value),
+        "show_utilization" => cfg.network.show_utilization = value.as_bool().unwrap_or(false),
+        _ => return Err(format!("Unknown network key: {}", key)),
+    }
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn save_latency(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<bool, String> {
+    let mut cfg = state.0.lock().unwrap();
+    match key.as_str() {
+        "targets" => {
+            cfg.latency.targets = value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        "interval_ms" => cfg.latency.interval_ms = value.as_u64().unwrap_or(2000).clamp(250, 60_000),
+        _ => return Err(format!("Unknown latency key: {}", key)),
+    }
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn save_power(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<bool, String> {
+    let mut cfg = state.0.lock().unwrap();
+    match key.as_str() {
+        "price_per_kwh" => cfg.power.price_per_kwh = value.as_f64().filter(|p| *p > 0.0),
+        "currency" => cfg.power.currency = value.as_str().unwrap_or("").trim().to_string(),
+        _ => return Err(format!("Unknown power key: {}", key)),
+    }
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn save_sampling(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<bool, String> {
+    let group = MetricGroup::from_key(&key).ok_or_else(|| format!("Unknown sampling key: {}", key))?;
+    let ms = value.as_u64().ok_or_else(|| format!("Invalid interval for {}", key))?;
+    let mut cfg = state.0.lock().unwrap();
+    cfg.sampling.set_interval_ms(group, ms);
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn save_processes(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    key: String,
+    value: serde_json::Value,
+) -> Result<bool, String> {
+    let mut cfg = state.0.lock().unwrap();
+    match key.as_str() {
+        "count" => cfg.processes.count = value.as_u64().unwrap_or(5).clamp(1, 50) as usize,
+        "sort_by" => {
+            cfg.processes.sort_by = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        }
+        "group_by_exe" => cfg.processes.group_by_exe = value.as_bool().unwrap_or(true),
+        _ => return Err(format!("Unknown processes key: {}", key)),
+    }
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
+    Ok(true)
+}
+
+/// `target` is an executable name or a numeric PID; empty stops tracking.
+#[tauri::command]
+pub fn save_tracked_process(app: AppHandle, state: State<'_, ConfigState>, target: String) -> Result<bool, String> {
+    let target = target.trim();
+    let mut cfg = state.0.lock().unwrap();
+    cfg.tracked_process = match target.parse::<u32>() {
+        _ if target.is_empty() => Default::default(),
+        Ok(pid) => TrackedProcessConfig { name: None, pid: Some(pid) },
+        Err(_) => TrackedProcessConfig { name: Some(target.to_string()), pid: None },
+    };
+    config::save_config(&cfg)?;
+    notify_config(&app, &cfg);
     Ok(true)
 }
 
@@ -99,8 +307,8 @@ pub fn save_appearance(
         }
     }
 
-    // Notificar overlay para buscar config atualizada (cor, fonte, etc.)
-    notify_overlay(&app);
+    // Notificar as janelas com a config atualizada (cor, fonte, etc.)
+    notify_config(&app, &cfg);
 
     Ok(true)
 }
@@ -186,7 +394,7 @@ pub fn reset_settings(app: AppHandle, state: State<'_, ConfigState>) -> Result<A
         let _ = overlay.set_position(tauri::PhysicalPosition::new(x, y));
     }
 
-    notify_overlay(&app);
+    notify_config(&app, &cfg);
     Ok(cfg.clone())
 }
 
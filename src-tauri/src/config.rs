@@ -1,56 +1,153 @@
+use crate::metrics::MetricGroup;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MetricsConfig {
-    #[serde(default = "default_true")]
-    pub cpu: bool,
-    #[serde(default)]
-    pub cpu_freq: bool,
-    #[serde(default = "default_true")]
-    pub ram: bool,
-    #[serde(default = "default_true")]
-    pub ram_gb: bool,
-    #[serde(default = "default_true")]
-    pub gpu: bool,
-    #[serde(default)]
-    pub gpu_temp: bool,
-    #[serde(default)]
-    pub gpu_power: bool,
-    #[serde(default)]
-    pub gpu_clock: bool,
-    #[serde(default)]
-    pub vram: bool,
-    #[serde(default = "default_true")]
-    pub disk_io: bool,
-    #[serde(default)]
-    pub net_io: bool,
+/// One overlay item the user can show, hide and reorder.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricToggle {
+    pub key: &'static str,
+    /// Settings label
+    pub label: &'static str,
+    /// Shown on a fresh install
+    pub default_shown: bool,
+    /// Metric IDs the overlay reads to display it; only these are collected
+    pub needs: &'static [&'static str],
 }
 
-fn default_true() -> bool {
-    true
+const fn hidden(key: &'static str, label: &'static str, needs: &'static [&'static str]) -> MetricToggle {
+    MetricToggle { key, label, default_shown: false, needs }
+}
+
+const fn shown(key: &'static str, label: &'static str, needs: &'static [&'static str]) -> MetricToggle {
+    MetricToggle { key, label, default_shown: true, needs }
+}
+
+/// Every built-in overlay item, in default display order.
+pub const METRIC_TOGGLES: &[MetricToggle] = &[
+    shown("cpu", "CPU % (Processor Usage)", &["cpu_percent"]),
+    hidden("cpu_user", "CPU User (Time in User Space)", &["cpu_user_percent"]),
+    hidden("cpu_nice", "CPU Nice (Low-Priority User Time)", &["cpu_nice_percent"]),
+    hidden("cpu_system", "CPU System (Time in Kernel)", &["cpu_system_percent"]),
+    hidden("cpu_iowait", "CPU I/O Wait (Idle Waiting on Disk)", &["cpu_iowait_percent"]),
+    hidden("cpu_irq", "CPU IRQ (Hardware Interrupts)", &["cpu_irq_percent"]),
+    hidden("cpu_softirq", "CPU SoftIRQ (Deferred Interrupt Work)", &["cpu_softirq_percent"]),
+    hidden("cpu_steal", "CPU Steal (Taken by Hypervisor)", &["cpu_steal_percent"]),
+    hidden("cpu_freq", "CPU Freq (Processor Clock in GHz)", &["cpu_freq_ghz"]),
+    hidden("cpu_max_core", "CPU Max Core % (Busiest Core)", &["cpu_max_core_percent"]),
+    hidden("cpu_max_freq", "CPU Max Freq (Fastest Core in GHz)", &["cpu_max_freq_ghz"]),
+    hidden("cpu_freq_headroom", "CPU Clock (% of Max Boost)", &["cpu_freq_percent_of_max"]),
+    hidden("cpu_governor", "CPU Frequency Governor", &["cpu_governor"]),
+    hidden("throttling", "Throttling Alert (CPU & GPU)", &["cpu_throttling", "gpu_throttle_reasons"]),
+    hidden("cpu_cores", "CPU Cores (Per-Core Usage Heatmap)", &["cpu_core_percent"]),
+    hidden("cpu_pcores", "P-Cores Usage & Clock (Hybrid CPUs)", &["pcore_percent", "pcore_freq_ghz"]),
+    hidden("cpu_ecores", "E-Cores Usage & Clock (Hybrid CPUs)", &["ecore_percent", "ecore_freq_ghz"]),
+    hidden("cpu_temp", "CPU Temp (Package Temperature)", &["cpu_temp"]),
+    hidden("cpu_power", "CPU Package Power (W)", &["cpu_package_w"]),
+    hidden("dram_power", "DRAM Power (W)", &["dram_w"]),
+    shown("ram", "RAM % (Memory Percentage)", &["ram_percent"]),
+    shown("ram_gb", "RAM GB (Memory in Gigabytes)", &["ram_used_gb", "ram_total_gb"]),
+    hidden("ram_available", "RAM Available (Free for New Allocations)", &["ram_available_gb"]),
+    hidden("ram_cached", "RAM Cached (Page Cache in GB)", &["ram_cached_gb"]),
+    hidden("ram_commit", "Commit (Commit Charge / Limit in GB)", &["commit_gb", "commit_limit_gb"]),
+    hidden("swap", "Swap (Swap / Pagefile Usage)", &["swap_percent", "swap_used_gb", "swap_total_gb"]),
+    shown("gpu", "GPU % (GPU Usage)", &["gpu_percent"]),
+    hidden("gpu_temp", "GPU Temp (GPU Temperature)", &["gpu_temp"]),
+    hidden("gpu_power", "GPU Power (Power Draw in Watts)", &["gpu_power_w"]),
+    // The GPU's draw is part of the session total
+    hidden("session_energy", "Session Energy (Wh and Cost)", &["session_energy_wh", "session_cost", "gpu_power_w"]),
+    hidden("gpu_clock", "GPU Clock (GPU Frequency in MHz)", &["gpu_clock_mhz"]),
+    hidden("vram", "VRAM (Video Memory)", &["vram_used_mb"]),
+    shown("disk_io", "Disk I/O (Read/Write MB/s)", &["disks", "disk_read_mb", "disk_write_mb"]),
+    hidden("disk_latency", "Disk Latency (Await, Queue Depth, Util %)", &["disks"]),
+    hidden("net_io", "Net I/O (↓Download ↑Upload MB/s)", &["net_down_mb", "net_up_mb", "net_utilization_percent"]),
+    hidden("tcp_retrans", "TCP Retransmits (% and /s)", &["tcp_retrans_percent", "tcp_retrans_per_sec"]),
+    hidden("net_errors", "Network Errors & Drops (/s)", &["net_errors_per_sec", "net_drops_per_sec"]),
+    hidden("tcp_conns", "Active TCP Connections", &["tcp_established"]),
+    hidden("latency", "Latency (TCP Connect Time)", &["latency"]),
+    hidden("latency_jitter", "Latency Jitter", &["latency"]),
+    hidden("latency_loss", "Latency Failed Probes %", &["latency"]),
+    hidden("nvme_temp", "NVMe Temp (Hottest SSD)", &["nvme_temp"]),
+    hidden("chipset_temp", "Chipset Temp (PCH)", &["chipset_temp"]),
+    hidden("fan", "Fan (Fastest Fan in RPM)", &["fan_rpm"]),
+    hidden("load_avg", "Load Average (1 / 5 / 15 min)", &["load_avg_1", "load_avg_5", "load_avg_15"]),
+    hidden("psi_cpu", "CPU Pressure (PSI some/full avg10)", &["psi_cpu_some", "psi_cpu_full"]),
+    hidden("psi_memory", "Memory Pressure (PSI some/full avg10)", &["psi_memory_some", "psi_memory_full"]),
+    hidden("psi_io", "I/O Pressure (PSI some/full avg10)", &["psi_io_some", "psi_io_full"]),
+    hidden("context_switches", "Context Switches (/s)", &["context_switches_per_sec"]),
+    hidden("interrupts", "Interrupts (/s)", &["interrupts_per_sec"]),
+    hidden("forks", "Process Creation (Forks/s)", &["forks_per_sec"]),
+    hidden("page_faults", "Page Faults (Minor/Major per s)", &["minor_faults_per_sec", "major_faults_per_sec"]),
+    hidden("top_process", "Top Process (Heaviest Process)", &["top_processes"]),
+    hidden("tracked_cpu", "Tracked App CPU %", &["tracked_process"]),
+    hidden("tracked_ram", "Tracked App RAM (Resident GB)", &["tracked_process"]),
+    hidden("tracked_threads", "Tracked App Threads", &["tracked_process"]),
+    hidden("tracked_handles", "Tracked App Handles / Open Files", &["tracked_process"]),
+    hidden("tracked_io", "Tracked App Disk I/O (Read/Write MB/s)", &["tracked_process"]),
+    hidden("battery", "Battery % (Hidden Without Battery)", &["battery_percent", "on_ac_power"]),
+    hidden("battery_rate", "Battery Charge/Discharge Rate (W)", &["battery_power_w"]),
+    hidden("battery_time", "Battery Time Remaining", &["battery_minutes_remaining", "battery_power_w"]),
+    hidden("battery_peripherals", "Wireless Peripheral Batteries", &["peripheral_batteries"]),
+];
+
+/// Which overlay items are shown, keyed by `METRIC_TOGGLES` key or by
+/// `fs:<mount>` for each watched mount. Stored as a flat `{"cpu": true, ...}`
+/// object; items missing from an older config file take their default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<String, bool>")]
+pub struct MetricsConfig(BTreeMap<String, bool>);
+
+impl From<BTreeMap<String, bool>> for MetricsConfig {
+    fn from(mut shown: BTreeMap<String, bool>) -> Self {
+        for toggle in METRIC_TOGGLES {
+            shown.entry(toggle.key.to_string()).or_insert(toggle.default_shown);
+        }
+        Self(shown)
+    }
 }
 
 impl Default for MetricsConfig {
     fn default() -> Self {
-        Self {
-            cpu: true,
-            cpu_freq: false,
-            ram: true,
-            ram_gb: true,
-            gpu: true,
-            gpu_temp: false,
-            gpu_power: false,
-            gpu_clock: false,
-            vram: false,
-            disk_io: true,
-            net_io: false,
+        Self::from(BTreeMap::new())
+    }
+}
+
+impl MetricsConfig {
+    pub fn shown(&self, key: &str) -> bool {
+        self.0.get(key).copied().unwrap_or(false)
+    }
+
+    /// Returns false for a key that is neither a toggle nor a watched mount.
+    pub fn set(&mut self, key: &str, shown: bool) -> bool {
+        match self.0.get_mut(key) {
+            Some(value) => {
+                *value = shown;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Metric IDs needed by the shown items; nothing else is collected.
+    pub fn wanted(&self) -> HashSet<&'static str> {
+        let mut wanted: HashSet<&'static str> = METRIC_TOGGLES
+            .iter()
+            .filter(|t| self.shown(t.key))
+            .flat_map(|t| t.needs.iter().copied())
+            .collect();
+        if self.0.iter().any(|(key, &shown)| shown && key.starts_with("fs:")) {
+            wanted.insert("filesystems");
         }
+        wanted
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppearanceConfig {
     #[serde(default)]
@@ -113,6 +210,283 @@ impl Default for PreferencesConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DiskConfig {
+    /// Disk shown by the `disk_io` metric; `None` shows the aggregate of all disks
+    #[serde(default)]
+    pub device: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Interface name patterns counted by `net_io`; empty counts every interface
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Patterns never counted, even when included (loopback, bridges, tunnels)
+    #[serde(default = "default_net_exclude")]
+    pub exclude: Vec<String>,
+    /// Also show `net_io` as a percentage of the link's negotiated speed
+    #[serde(default)]
+    pub show_utilization: bool,
+}
+
+fn default_net_exclude() -> Vec<String> {
+    [
+        "lo", "lo0", "Loopback*", "docker*", "br-*", "veth*", "virbr*", "vEthernet*", "tun*",
+        "tap*", "wg*", "utun*", "tailscale*",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: default_net_exclude(),
+            show_utilization: false,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// An interface counts when it matches an include pattern (or the list is
+    /// empty) and no exclude pattern.
+    pub fn allows(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name)))
+            && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+/// Case-insensitive wildcard match: `*` matches any run of characters, `?` exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    // Position of the last `*` seen and the text index it is currently covering up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star, covered)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            backtrack = Some((star, covered + 1));
+            pi = star + 1;
+            ti = covered + 1;
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// `host:port` pairs, e.g. a game server or `192.168.1.1:80` for the gateway
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default = "default_latency_interval_ms")]
+    pub interval_ms: u64,
+    /// A connect slower than this counts as a failure
+    #[serde(default = "default_latency_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Probes per target kept for the average, jitter and failure %
+    #[serde(default = "default_latency_samples")]
+    pub samples: usize,
+}
+
+fn default_latency_interval_ms() -> u64 {
+    2000
+}
+fn default_latency_timeout_ms() -> u64 {
+    1000
+}
+fn default_latency_samples() -> usize {
+    30
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            interval_ms: 2000,
+            timeout_ms: 1000,
+            samples: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSort {
+    #[default]
+    Cpu,
+    Memory,
+    Disk,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessesConfig {
+    /// How many processes `top_processes` lists
+    #[serde(default = "default_process_count")]
+    pub count: usize,
+    #[serde(default)]
+    pub sort_by: ProcessSort,
+    /// Fold processes with the same executable name (browser helpers...) into one entry
+    #[serde(default = "default_true")]
+    pub group_by_exe: bool,
+}
+
+fn default_process_count() -> usize {
+    5
+}
+
+impl Default for ProcessesConfig {
+    fn default() -> Self {
+        Self {
+            count: 5,
+            sort_by: ProcessSort::Cpu,
+            group_by_exe: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TrackedProcessConfig {
+    /// Executable to follow (`game.exe` or `game`); re-attached when it restarts
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Specific process, used only when no name is set
+    #[serde(default)]
+    pub pid: Option<u32>,
+}
+
+/// How often each metric group is refreshed by the background sampler, in ms.
+/// Cheap, fast-moving groups run every second; slow or costly ones less often.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingConfig {
+    pub cpu_ms: u64,
+    pub memory_ms: u64,
+    pub gpu_ms: u64,
+    pub power_ms: u64,
+    pub disk_ms: u64,
+    pub filesystems_ms: u64,
+    pub network_ms: u64,
+    pub sensors_ms: u64,
+    /// Load average, pressure stalls and kernel event rates
+    pub system_ms: u64,
+    pub processes_ms: u64,
+    pub battery_ms: u64,
+}
+
+impl SamplingConfig {
+    const MIN_MS: u64 = 250;
+    const MAX_MS: u64 = 300_000;
+
+    /// Clamped on read as well, so a hand-edited `"cpu_ms": 0` in the config
+    /// file can't make the sampler spin without sleeping.
+    pub fn interval_ms(&self, group: MetricGroup) -> u64 {
+        (*self.field(group)).clamp(Self::MIN_MS, Self::MAX_MS)
+    }
+
+    pub fn set_interval_ms(&mut self, group: MetricGroup, ms: u64) {
+        *self.field_mut(group) = ms.clamp(Self::MIN_MS, Self::MAX_MS);
+    }
+
+    fn field(&self, group: MetricGroup) -> &u64 {
+        match group {
+            MetricGroup::Cpu => &self.cpu_ms,
+            MetricGroup::Memory => &self.memory_ms,
+            MetricGroup::Gpu => &self.gpu_ms,
+            MetricGroup::Power => &self.power_ms,
+            MetricGroup::Disk => &self.disk_ms,
+            MetricGroup::Filesystems => &self.filesystems_ms,
+            MetricGroup::Network => &self.network_ms,
+            MetricGroup::Sensors => &self.sensors_ms,
+            MetricGroup::System => &self.system_ms,
+            MetricGroup::Processes => &self.processes_ms,
+            MetricGroup::Battery => &self.battery_ms,
+        }
+    }
+
+    fn field_mut(&mut self, group: MetricGroup) -> &mut u64 {
+        match group {
+            MetricGroup::Cpu => &mut self.cpu_ms,
+            MetricGroup::Memory => &mut self.memory_ms,
+            MetricGroup::Gpu => &mut self.gpu_ms,
+            MetricGroup::Power => &mut self.power_ms,
+            MetricGroup::Disk => &mut self.disk_ms,
+            MetricGroup::Filesystems => &mut self.filesystems_ms,
+            MetricGroup::Network => &mut self.network_ms,
+            MetricGroup::Sensors => &mut self.sensors_ms,
+            MetricGroup::System => &mut self.system_ms,
+            MetricGroup::Processes => &mut self.processes_ms,
+            MetricGroup::Battery => &mut self.battery_ms,
+        }
+    }
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            cpu_ms: 1000,
+            memory_ms: 1000,
+            gpu_ms: 1000,
+            power_ms: 1000,
+            disk_ms: 1000,
+            filesystems_ms: 60_000,
+            network_ms: 1000,
+            sensors_ms: 2000,
+            system_ms: 1000,
+            processes_ms: 2000,
+            battery_ms: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// Electricity price used for the session cost; no cost is shown without one
+    #[serde(default)]
+    pub price_per_kwh: Option<f64>,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+fn default_currency() -> String {
+    "$".to_string()
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            price_per_kwh: None,
+            currency: default_currency(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilesystemsConfig {
+    /// Mount points (`/`, `/home`) or drive letters (`C:`) to report space for
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    #[serde(default)]
+    pub show_inodes: bool,
+}
+
+/// `MetricsConfig` key of a watched mount, e.g. `fs:/home` or `fs:C:`
+pub fn filesystem_metric_key(mount: &str) -> String {
+    format!("fs:{}", mount)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
@@ -123,22 +497,26 @@ pub struct AppConfig {
     pub appearance: AppearanceConfig,
     #[serde(default)]
     pub preferences: PreferencesConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub latency: LatencyConfig,
+    #[serde(default)]
+    pub processes: ProcessesConfig,
+    #[serde(default)]
+    pub tracked_process: TrackedProcessConfig,
+    #[serde(default)]
+    pub filesystems: FilesystemsConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub sampling: SamplingConfig,
 }
 
 fn default_metrics_order() -> Vec<String> {
-    vec![
-        "cpu".into(),
-        "cpu_freq".into(),
-        "ram".into(),
-        "ram_gb".into(),
-        "gpu".into(),
-        "gpu_temp".into(),
-        "gpu_power".into(),
-        "gpu_clock".into(),
-        "vram".into(),
-        "disk_io".into(),
-        "net_io".into(),
-    ]
+    METRIC_TOGGLES.iter().map(|t| t.key.to_string()).collect()
 }
 
 impl Default for AppConfig {
@@ -148,7 +526,41 @@ impl Default for AppConfig {
             metrics_order: default_metrics_order(),
             appearance: AppearanceConfig::default(),
             preferences: PreferencesConfig::default(),
+            disk: DiskConfig::default(),
+            network: NetworkConfig::default(),
+            latency: LatencyConfig::default(),
+            processes: ProcessesConfig::default(),
+            tracked_process: TrackedProcessConfig::default(),
+            filesystems: FilesystemsConfig::default(),
+            power: PowerConfig::default(),
+            sampling: SamplingConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Replaces the watched mounts. Each new mount gets its own metric, shown
+    /// and placed after the disk metrics; metrics of removed mounts are dropped.
+    pub fn set_filesystem_mounts(&mut self, mounts: Vec<String>) {
+        let keys: Vec<String> = mounts.iter().map(|m| filesystem_metric_key(m)).collect();
+
+        self.metrics_order.retain(|k| !k.starts_with("fs:") || keys.contains(k));
+        self.metrics.0.retain(|k, _| !k.starts_with("fs:") || keys.contains(k));
+
+        for key in &keys {
+            if self.metrics_order.contains(key) {
+                continue;
+            }
+            let pos = self
+                .metrics_order
+                .iter()
+                .rposition(|k| k.starts_with("fs:") || k == "disk_io")
+                .map_or(self.metrics_order.len(), |p| p + 1);
+            self.metrics_order.insert(pos, key.clone());
+            self.metrics.0.insert(key.clone(), true);
         }
+
+        self.filesystems.mounts = mounts;
     }
 }
 
@@ -172,9 +584,29 @@ pub fn config_path() -> PathBuf {
 
 pub fn load_config() -> AppConfig {
     let path = config_path();
-    match fs::read_to_string(&path) {
+    let mut cfg: AppConfig = match fs::read_to_string(&path) {
         Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
         Err(_) => AppConfig::default(),
+    };
+    merge_new_metrics(&mut cfg.metrics_order);
+    cfg
+}
+
+/// Configs saved by older versions don't list metrics added since, which would
+/// hide them from Settings. Each missing one is slotted in after its
+/// predecessor in the default order.
+fn merge_new_metrics(order: &mut Vec<String>) {
+    let defaults = default_metrics_order();
+    for (i, key) in defaults.iter().enumerate() {
+        if order.contains(key) {
+            continue;
+        }
+        let pos = defaults[..i]
+            .iter()
+            .rev()
+            .find_map(|prev| order.iter().position(|k| k == prev))
+            .map_or(0, |p| p + 1);
+        order.insert(pos, key.clone());
     }
 }
 
@@ -184,3 +616,26 @@ pub fn save_config(config: &AppConfig) -> Result<(), String> {
     fs::write(&path, json).map_err(|e| e.to_string())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_intervals_are_clamped() {
+        let sampling: SamplingConfig = serde_json::from_str(r#"{"cpu_ms": 0, "disk_ms": 86400000}"#).unwrap();
+        assert_eq!(sampling.interval_ms(MetricGroup::Cpu), 250);
+        assert_eq!(sampling.interval_ms(MetricGroup::Disk), 300_000);
+        // Fields missing from the file keep their defaults
+        assert_eq!(sampling.interval_ms(MetricGroup::Filesystems), 60_000);
+    }
+
+    #[test]
+    fn set_interval_clamps() {
+        let mut sampling = SamplingConfig::default();
+        sampling.set_interval_ms(MetricGroup::Network, 0);
+        sampling.set_interval_ms(MetricGroup::Battery, u64::MAX);
+        assert_eq!(sampling.network_ms, 250);
+        assert_eq!(sampling.battery_ms, 300_000);
+    }
+}
@@ -0,0 +1,113 @@
+/// Share of CPU time spent in each state since the previous sample, as
+/// percentages of all CPU time across every core.
+#[derive(Debug, Clone, Default)]
+pub struct CpuTimes {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    /// Time the hypervisor ran something else while this VM wanted the CPU
+    pub steal: f32,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: aggregate `cpu` line of /proc/stat
+// `cpu  user nice system idle iowait irq softirq steal guest guest_nice`, in
+// jiffies since boot. guest/guest_nice are already included in user/nice.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct CpuTimeMonitor {
+    stat: std::path::PathBuf,
+    last: Option<[u64; 8]>,
+}
+
+#[cfg(target_os = "linux")]
+impl CpuTimeMonitor {
+    pub fn new() -> Self {
+        Self::with_proc_root("/proc")
+    }
+
+    /// Reads `<proc_root>/stat` instead of the live file.
+    pub fn with_proc_root(proc_root: impl Into<std::path::PathBuf>) -> Self {
+        Self { stat: proc_root.into().join("stat"), last: None }
+    }
+
+    /// `None` until two samples are available.
+    pub fn refresh(&mut self) -> Option<CpuTimes> {
+        let content = std::fs::read_to_string(&self.stat).ok()?;
+        let current = parse_cpu_line(&content)?;
+        let last = self.last.replace(current)?;
+
+        let delta: Vec<u64> = current.iter().zip(last.iter()).map(|(c, l)| c.saturating_sub(*l)).collect();
+        let total: u64 = delta.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let pct = |i: usize| (delta[i] as f64 / total as f64 * 100.0) as f32;
+
+        Some(CpuTimes {
+            user: pct(0),
+            nice: pct(1),
+            system: pct(2),
+            iowait: pct(4),
+            irq: pct(5),
+            softirq: pct(6),
+            steal: pct(7),
+        })
+    }
+}
+
+/// First eight fields of the aggregate `cpu` line (user through steal).
+/// Older kernels print fewer columns; missing ones count as 0.
+#[cfg(target_os = "linux")]
+fn parse_cpu_line(content: &str) -> Option<[u64; 8]> {
+    let line = content.lines().find(|l| l.starts_with("cpu "))?;
+    let mut fields = [0u64; 8];
+    for (slot, value) in fields.iter_mut().zip(line.split_whitespace().skip(1)) {
+        *slot = value.parse().ok()?;
+    }
+    Some(fields)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct CpuTimeMonitor;
+
+#[cfg(not(target_os = "linux"))]
+impl CpuTimeMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> Option<CpuTimes> {
+        None
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    #[test]
+    fn percentages_of_time_between_samples() {
+        let proc_root = FixtureDir::new();
+        let mut monitor = CpuTimeMonitor::with_proc_root(proc_root.path());
+
+        proc_root.write("stat", "cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 100 0 100 700 100 0 0 0 0 0\n");
+        assert!(monitor.refresh().is_none());
+
+        // 2000 jiffies: user 400, system 200, idle 1000, iowait 200, irq 100, softirq 100
+        proc_root.write("stat", "cpu  500 0 300 1700 300 100 100 0 0 0\ncpu0 500 0 300 1700 300 100 100 0 0 0\n");
+        let times = monitor.refresh().unwrap();
+        assert_eq!((times.user, times.nice, times.system), (20.0, 0.0, 10.0));
+        assert_eq!((times.iowait, times.irq, times.softirq, times.steal), (10.0, 5.0, 5.0, 0.0));
+    }
+
+    #[test]
+    fn short_cpu_line_from_older_kernels() {
+        assert_eq!(parse_cpu_line("cpu  10 0 20 70\n"), Some([10, 0, 20, 70, 0, 0, 0, 0]));
+        assert_eq!(parse_cpu_line("intr 1 2 3\n"), None);
+    }
+}
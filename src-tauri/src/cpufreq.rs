@@ -0,0 +1,201 @@
+use std::time::{Duration, Instant};
+
+/// A throttle event keeps the indicator on this long, so a single event
+/// between two samples doesn't just flicker past.
+const THROTTLE_HOLD: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuFreqInfo {
+    /// Highest boost clock any core can reach
+    pub max_mhz: Option<u64>,
+    /// cpufreq scaling governor (Linux), e.g. `powersave`, `performance`
+    pub governor: Option<String>,
+    pub throttling: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /sys/devices/system/cpu/cpuN/{cpufreq,thermal_throttle}
+// cpuinfo_max_freq is in kHz. thermal_throttle/*_throttle_count are cumulative
+// counts of PROCHOT events (Intel only); an increase means we throttled.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct CpuFreqMonitor {
+    cpu_dirs: Vec<std::path::PathBuf>,
+    max_mhz: Option<u64>,
+    last_throttle_count: Option<u64>,
+    last_throttle: Option<Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl CpuFreqMonitor {
+    pub fn new() -> Self {
+        Self::with_sysfs_root("/sys/devices/system/cpu")
+    }
+
+    /// Reads `<root>/cpuN/...` instead of the live sysfs tree.
+    pub fn with_sysfs_root(root: impl AsRef<std::path::Path>) -> Self {
+        let mut cpu_dirs: Vec<std::path::PathBuf> = std::fs::read_dir(root.as_ref())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| {
+                        let name = e.file_name();
+                        let name = name.to_string_lossy();
+                        name.strip_prefix("cpu").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+                    })
+                    .map(|e| e.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        cpu_dirs.sort();
+
+        // The boost ceiling doesn't change at runtime, so it is read once
+        let max_mhz = cpu_dirs
+            .iter()
+            .filter_map(|dir| read_u64(&dir.join("cpufreq/cpuinfo_max_freq")))
+            .max()
+            .map(|khz| khz / 1000);
+
+        Self {
+            cpu_dirs,
+            max_mhz,
+            last_throttle_count: None,
+            last_throttle: None,
+        }
+    }
+
+    pub fn refresh(&mut self, now: Instant) -> CpuFreqInfo {
+        let governor = self
+            .cpu_dirs
+            .first()
+            .and_then(|dir| std::fs::read_to_string(dir.join("cpufreq/scaling_governor")).ok())
+            .map(|g| g.trim().to_string());
+
+        let counts: Vec<u64> = self
+            .cpu_dirs
+            .iter()
+            .flat_map(|dir| ["core_throttle_count", "package_throttle_count"].map(|f| dir.join("thermal_throttle").join(f)))
+            .filter_map(|path| read_u64(&path))
+            .collect();
+        if !counts.is_empty() {
+            let total: u64 = counts.iter().sum();
+            if self.last_throttle_count.replace(total).is_some_and(|last| total > last) {
+                self.last_throttle = Some(now);
+            }
+        }
+
+        CpuFreqInfo {
+            max_mhz: self.max_mhz,
+            governor,
+            throttling: self
+                .last_throttle
+                .is_some_and(|t| now.saturating_duration_since(t) < THROTTLE_HOLD),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_u64(path: &std::path::Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// ---------------------------------------------------------------------------
+// Windows: CallNtPowerInformation(ProcessorInformation)
+// One PROCESSOR_POWER_INFORMATION per logical CPU; MhzLimit drops below
+// MaxMhz while the firmware or OS caps the clock (thermal or power limit).
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "windows")]
+pub struct CpuFreqMonitor {
+    cpu_count: usize,
+    last_throttle: Option<Instant>,
+}
+
+#[cfg(target_os = "windows")]
+impl CpuFreqMonitor {
+    pub fn new() -> Self {
+        use windows_sys::Win32::System::Threading::{GetActiveProcessorCount, ALL_PROCESSOR_GROUPS};
+        let cpu_count = unsafe { GetActiveProcessorCount(ALL_PROCESSOR_GROUPS) } as usize;
+        Self { cpu_count: cpu_count.max(1), last_throttle: None }
+    }
+
+    pub fn refresh(&mut self, now: Instant) -> CpuFreqInfo {
+        use windows_sys::Win32::System::Power::{CallNtPowerInformation, ProcessorInformation, PROCESSOR_POWER_INFORMATION};
+
+        let mut info: Vec<PROCESSOR_POWER_INFORMATION> = vec![unsafe { std::mem::zeroed() }; self.cpu_count];
+        let size = (info.len() * std::mem::size_of::<PROCESSOR_POWER_INFORMATION>()) as u32;
+        let status = unsafe {
+            CallNtPowerInformation(ProcessorInformation, std::ptr::null(), 0, info.as_mut_ptr().cast(), size)
+        };
+        if status != 0 {
+            return CpuFreqInfo::default();
+        }
+
+        if info.iter().any(|p| p.MaxMhz > 0 && p.MhzLimit < p.MaxMhz) {
+            self.last_throttle = Some(now);
+        }
+
+        CpuFreqInfo {
+            max_mhz: info.iter().map(|p| p.MaxMhz as u64).max().filter(|&m| m > 0),
+            governor: None,
+            throttling: self
+                .last_throttle
+                .is_some_and(|t| now.saturating_duration_since(t) < THROTTLE_HOLD),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub struct CpuFreqMonitor;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl CpuFreqMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self, _now: Instant) -> CpuFreqInfo {
+        CpuFreqInfo::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    fn cpu_tree() -> FixtureDir {
+        let root = FixtureDir::new();
+        root.write("cpu0/cpufreq/cpuinfo_max_freq", "4200000\n")
+            .write("cpu0/cpufreq/scaling_governor", "powersave\n")
+            .write("cpu0/thermal_throttle/core_throttle_count", "3\n")
+            .write("cpu0/thermal_throttle/package_throttle_count", "1\n")
+            .write("cpu1/cpufreq/cpuinfo_max_freq", "4700000\n")
+            .write("cpu1/thermal_throttle/core_throttle_count", "0\n")
+            // Not a CPU directory
+            .write("cpufreq/boost", "1\n");
+        root
+    }
+
+    #[test]
+    fn max_clock_and_governor() {
+        let root = cpu_tree();
+        let info = CpuFreqMonitor::with_sysfs_root(root.path()).refresh(Instant::now());
+        assert_eq!(info.max_mhz, Some(4700));
+        assert_eq!(info.governor.as_deref(), Some("powersave"));
+        // The first sample only primes the count
+        assert!(!info.throttling);
+    }
+
+    #[test]
+    fn throttle_count_increase_holds_indicator() {
+        let root = cpu_tree();
+        let mut monitor = CpuFreqMonitor::with_sysfs_root(root.path());
+        let t0 = Instant::now();
+        monitor.refresh(t0);
+
+        root.write("cpu1/thermal_throttle/core_throttle_count", "1\n");
+        assert!(monitor.refresh(t0 + Duration::from_secs(1)).throttling);
+        assert!(monitor.refresh(t0 + Duration::from_secs(2)).throttling);
+        assert!(!monitor.refresh(t0 + Duration::from_secs(1) + THROTTLE_HOLD).throttling);
+    }
+}
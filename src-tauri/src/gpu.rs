@@ -1,4 +1,5 @@
 use nvml_wrapper::{
+    bitmasks::device::ThrottleReasons,
     enum_wrappers::device::{Clock, TemperatureSensor},
     Nvml,
 };
@@ -10,6 +11,8 @@ pub struct GpuStats {
     pub clock_mhz: Option<u32>,
     pub vram_used_mb: Option<u32>,
     pub vram_total_mb: Option<u32>,
+    /// Active clock limiters; idle and app/user clock settings are not throttling
+    pub throttle_reasons: Vec<String>,
 }
 
 impl Default for GpuStats {
@@ -21,6 +24,7 @@ impl Default for GpuStats {
             clock_mhz: None,
             vram_used_mb: None,
             vram_total_mb: None,
+            throttle_reasons: Vec::new(),
         }
     }
 }
@@ -53,6 +57,23 @@ impl GpuMonitor {
             Err(_) => (None, None),
         };
 
-        GpuStats { percent, temp, power_w, clock_mhz, vram_used_mb, vram_total_mb }
+        let throttle_reasons = device
+            .current_throttle_reasons()
+            .map(|reasons| {
+                [
+                    (ThrottleReasons::SW_POWER_CAP, "power cap"),
+                    (ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN, "power brake"),
+                    (ThrottleReasons::SW_THERMAL_SLOWDOWN, "thermal"),
+                    (ThrottleReasons::HW_THERMAL_SLOWDOWN, "hw thermal"),
+                    (ThrottleReasons::HW_SLOWDOWN, "hw slowdown"),
+                ]
+                .into_iter()
+                .filter(|(flag, _)| reasons.contains(*flag))
+                .map(|(_, name)| name.to_string())
+                .collect()
+            })
+            .unwrap_or_default();
+
+        GpuStats { percent, temp, power_w, clock_mhz, vram_used_mb, vram_total_mb, throttle_reasons }
     }
 }
@@ -0,0 +1,153 @@
+/// Logical CPU indices (same order as sysinfo's `cpus()`) of each core type on
+/// a hybrid CPU. Detected once at startup; the topology doesn't change.
+#[derive(Debug, Clone, Default)]
+pub struct CoreTypes {
+    pub performance: Vec<usize>,
+    pub efficiency: Vec<usize>,
+}
+
+impl CoreTypes {
+    /// `None` on CPUs with a single core type, where a split means nothing.
+    pub fn detect() -> Option<Self> {
+        let types = detect_core_types()?;
+        (!types.performance.is_empty() && !types.efficiency.is_empty()).then_some(types)
+    }
+}
+
+/// Average usage (%) and clock (GHz) over a subset of logical CPUs.
+pub fn group_average(indices: &[usize], percent: &[f32], freq_mhz: &[u64]) -> Option<(f32, f32)> {
+    let cores: Vec<usize> = indices.iter().copied().filter(|&i| i < percent.len() && i < freq_mhz.len()).collect();
+    if cores.is_empty() {
+        return None;
+    }
+    let n = cores.len() as f32;
+    let usage = cores.iter().map(|&i| percent[i]).sum::<f32>() / n;
+    let ghz = cores.iter().map(|&i| freq_mhz[i] as f32).sum::<f32>() / n / 1000.0;
+    Some((usage, ghz))
+}
+
+// ---------------------------------------------------------------------------
+// Linux: the hybrid PMU driver registers one device per core type, each with
+// a `cpus` list such as `0-15` or `16-23` (Intel 12th gen and newer)
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+fn detect_core_types() -> Option<CoreTypes> {
+    detect_core_types_in("/sys/devices")
+}
+
+/// Reads `<root>/cpu_core/cpus` and `<root>/cpu_atom/cpus`.
+#[cfg(target_os = "linux")]
+fn detect_core_types_in(root: impl AsRef<std::path::Path>) -> Option<CoreTypes> {
+    let read = |device: &str| -> Option<Vec<usize>> {
+        let list = std::fs::read_to_string(root.as_ref().join(device).join("cpus")).ok()?;
+        parse_cpu_list(list.trim())
+    };
+    Some(CoreTypes {
+        performance: read("cpu_core")?,
+        efficiency: read("cpu_atom")?,
+    })
+}
+
+/// Parses the kernel's cpu list format: `0-7,16,18-19`.
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for part in list.split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?),
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+// ---------------------------------------------------------------------------
+// Windows: GetLogicalProcessorInformationEx(RelationProcessorCore)
+// Each physical core reports an EfficiencyClass; on hybrid parts the highest
+// class is the P-cores. Only processor group 0 is mapped (up to 64 threads).
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "windows")]
+fn detect_core_types() -> Option<CoreTypes> {
+    use windows_sys::Win32::System::SystemInformation::{
+        GetLogicalProcessorInformationEx, RelationProcessorCore, SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX,
+    };
+
+    let mut len: u32 = 0;
+    unsafe { GetLogicalProcessorInformationEx(RelationProcessorCore, std::ptr::null_mut(), &mut len) };
+    if len == 0 {
+        return None;
+    }
+    // Records are variable-length; a u64 buffer keeps them aligned
+    let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+    let base = buffer.as_mut_ptr() as *mut u8;
+    if unsafe { GetLogicalProcessorInformationEx(RelationProcessorCore, base.cast(), &mut len) } == 0 {
+        return None;
+    }
+
+    // (efficiency class, logical CPUs of the core)
+    let mut cores: Vec<(u8, Vec<usize>)> = Vec::new();
+    let mut offset = 0usize;
+    while offset < len as usize {
+        let record = unsafe { &*(base.add(offset) as *const SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX) };
+        if record.Size == 0 {
+            break;
+        }
+        let processor = unsafe { &record.Anonymous.Processor };
+        let mask = processor.GroupMask[0];
+        if mask.Group == 0 {
+            let cpus = (0..usize::BITS as usize).filter(|bit| mask.Mask & (1 << bit) != 0).collect();
+            cores.push((processor.EfficiencyClass, cpus));
+        }
+        offset += record.Size as usize;
+    }
+
+    let top_class = cores.iter().map(|(class, _)| *class).max()?;
+    let mut types = CoreTypes::default();
+    for (class, cpus) in cores {
+        if class == top_class {
+            types.performance.extend(cpus);
+        } else {
+            types.efficiency.extend(cpus);
+        }
+    }
+    types.performance.sort_unstable();
+    types.efficiency.sort_unstable();
+    Some(types)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn detect_core_types() -> Option<CoreTypes> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_average_skips_unknown_indices() {
+        let percent = [10.0, 30.0, 80.0];
+        let freq_mhz = [1000, 3000, 4000];
+        assert_eq!(group_average(&[0, 1, 7], &percent, &freq_mhz), Some((20.0, 2.0)));
+        assert_eq!(group_average(&[7], &percent, &freq_mhz), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn core_types_from_pmu_cpu_lists() {
+        let root = crate::fixtures::FixtureDir::new();
+        root.write("cpu_core/cpus", "0-3,8\n").write("cpu_atom/cpus", "4-7\n");
+
+        let types = detect_core_types_in(root.path()).unwrap();
+        assert_eq!(types.performance, [0, 1, 2, 3, 8]);
+        assert_eq!(types.efficiency, [4, 5, 6, 7]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn single_core_type_is_not_hybrid() {
+        let root = crate::fixtures::FixtureDir::new();
+        root.write("cpu/cpus", "0-7\n");
+        assert!(detect_core_types_in(root.path()).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(target_os = "linux")]
+use crate::rate::RateCounter;
+use std::time::Instant;
+
+/// Kernel event rates, per second. `None` where the platform has no counter.
+#[derive(Debug, Clone, Default)]
+pub struct KernelActivity {
+    pub context_switches: Option<f32>,
+    pub interrupts: Option<f32>,
+    /// Processes and threads created
+    pub forks: Option<f32>,
+    pub minor_faults: Option<f32>,
+    /// Faults that had to read from disk (swap or a mapped file)
+    pub major_faults: Option<f32>,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /proc/stat and /proc/vmstat, cumulative since boot
+// /proc/stat:   `ctxt 123`, `intr 456 <per-IRQ counts...>`, `processes 789`
+// /proc/vmstat: `pgfault 123`, `pgmajfault 4` (pgfault includes major faults)
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct KernelActivityMonitor {
+    proc_root: std::path::PathBuf,
+    context_switches: RateCounter,
+    interrupts: RateCounter,
+    forks: RateCounter,
+    minor_faults: RateCounter,
+    major_faults: RateCounter,
+}
+
+#[cfg(target_os = "linux")]
+impl KernelActivityMonitor {
+    pub fn new() -> Self {
+        Self::with_proc_root("/proc")
+    }
+
+    /// Reads `<proc_root>/{stat,vmstat}` instead of the live files.
+    pub fn with_proc_root(proc_root: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            proc_root: proc_root.into(),
+            context_switches: RateCounter::default(),
+            interrupts: RateCounter::default(),
+            forks: RateCounter::default(),
+            minor_faults: RateCounter::default(),
+            major_faults: RateCounter::default(),
+        }
+    }
+
+    pub fn refresh(&mut self, now: Instant) -> KernelActivity {
+        let stat = std::fs::read_to_string(self.proc_root.join("stat")).unwrap_or_default();
+        let vmstat = std::fs::read_to_string(self.proc_root.join("vmstat")).unwrap_or_default();
+
+        // First number after the key; `intr` is followed by per-IRQ counts
+        let counter = |content: &str, key: &str| -> Option<u64> {
+            content.lines().find_map(|line| {
+                let mut fields = line.split_whitespace();
+                if fields.next()? != key {
+                    return None;
+                }
+                fields.next()?.parse().ok()
+            })
+        };
+
+        let faults = counter(&vmstat, "pgfault");
+        let major = counter(&vmstat, "pgmajfault");
+        let minor = faults.zip(major).map(|(all, major)| all.saturating_sub(major));
+
+        let rate = |counter: &mut RateCounter, value: Option<u64>| value.map(|v| counter.update(now, v) as f32);
+        KernelActivity {
+            context_switches: rate(&mut self.context_switches, counter(&stat, "ctxt")),
+            interrupts: rate(&mut self.interrupts, counter(&stat, "intr")),
+            forks: rate(&mut self.forks, counter(&stat, "processes")),
+            minor_faults: rate(&mut self.minor_faults, minor),
+            major_faults: rate(&mut self.major_faults, major),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct KernelActivityMonitor;
+
+#[cfg(not(target_os = "linux"))]
+impl KernelActivityMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self, _now: Instant) -> KernelActivity {
+        KernelActivity::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+    use std::time::Duration;
+
+    fn write(proc_root: &FixtureDir, ctxt: u64, intr: u64, processes: u64, pgfault: u64, pgmajfault: u64) {
+        proc_root
+            .write(
+                "stat",
+                &format!("cpu  1 2 3 4\nintr {} 7 0 3\nctxt {}\nbtime 1700000000\nprocesses {}\n", intr, ctxt, processes),
+            )
+            .write("vmstat", &format!("nr_free_pages 1000\npgfault {}\npgmajfault {}\n", pgfault, pgmajfault));
+    }
+
+    #[test]
+    fn event_rates_between_samples() {
+        let proc_root = FixtureDir::new();
+        let mut monitor = KernelActivityMonitor::with_proc_root(proc_root.path());
+        let t0 = Instant::now();
+
+        write(&proc_root, 1000, 5000, 100, 10_000, 100);
+        assert_eq!(monitor.refresh(t0).context_switches, Some(0.0));
+
+        write(&proc_root, 3000, 9000, 110, 12_100, 120);
+        let activity = monitor.refresh(t0 + Duration::from_secs(2));
+        assert_eq!(activity.context_switches, Some(1000.0));
+        // The total after `intr`, not a per-IRQ count
+        assert_eq!(activity.interrupts, Some(2000.0));
+        assert_eq!(activity.forks, Some(5.0));
+        // pgfault includes the 20 major faults
+        assert_eq!(activity.minor_faults, Some(1040.0));
+        assert_eq!(activity.major_faults, Some(10.0));
+    }
+
+    #[test]
+    fn missing_files_yield_none() {
+        let proc_root = FixtureDir::new();
+        let activity = KernelActivityMonitor::with_proc_root(proc_root.path()).refresh(Instant::now());
+        assert_eq!(activity.context_switches, None);
+        assert_eq!(activity.major_faults, None);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::config::LatencyConfig;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    /// `host:port` as configured
+    pub target: String,
+    /// Last successful connect, `None` if the last probe failed
+    pub current_ms: Option<f32>,
+    pub avg_ms: Option<f32>,
+    /// Mean difference between consecutive successful samples
+    pub jitter_ms: Option<f32>,
+    /// Share of probes in the window that failed or timed out
+    pub failure_percent: f32,
+}
+
+/// Rolling window of connect times for one target; `None` is a failed probe.
+#[derive(Default)]
+struct TargetHistory {
+    samples: VecDeque<Option<f32>>,
+}
+
+impl TargetHistory {
+    fn push(&mut self, sample: Option<f32>, window: usize) {
+        self.samples.push_back(sample);
+        while self.samples.len() > window.max(1) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn stats(&self, target: &str) -> LatencyStats {
+        let ok: Vec<f32> = self.samples.iter().flatten().copied().collect();
+        let avg_ms = (!ok.is_empty()).then(|| ok.iter().sum::<f32>() / ok.len() as f32);
+        let jitter_ms = (ok.len() > 1).then(|| {
+            let diffs: f32 = ok.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+            diffs / (ok.len() - 1) as f32
+        });
+        let failures = self.samples.iter().filter(|s| s.is_none()).count();
+        LatencyStats {
+            target: target.to_string(),
+            current_ms: self.samples.back().copied().flatten(),
+            avg_ms,
+            jitter_ms,
+            failure_percent: if self.samples.is_empty() {
+                0.0
+            } else {
+                failures as f32 / self.samples.len() as f32 * 100.0
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    config: LatencyConfig,
+    history: HashMap<String, TargetHistory>,
+}
+
+/// The condvar wakes the probe thread early when the config changes
+type Shared = (Mutex<State>, Condvar);
+
+/// Measures TCP connect time to each configured `host:port` on its own
+/// thread, so an unreachable target never holds up `MetricRegistry::refresh`.
+/// The thread exits once the probe is dropped.
+pub struct LatencyProbe {
+    shared: Arc<Shared>,
+}
+
+impl LatencyProbe {
+    pub fn new() -> Self {
+        let shared: Arc<Shared> = Arc::new((Mutex::new(State::default()), Condvar::new()));
+        let weak = Arc::downgrade(&shared);
+        let _ = std::thread::Builder::new()
+            .name("latency-probe".into())
+            .spawn(move || run(weak));
+        Self { shared }
+    }
+
+    /// Applies target and interval changes from Settings; history is kept for
+    /// targets that stay configured.
+    pub fn configure(&self, cfg: &LatencyConfig) {
+        let mut state = self.shared.0.lock().unwrap();
+        if state.config != *cfg {
+            state.history.retain(|target, _| cfg.targets.contains(target));
+            state.config = cfg.clone();
+            self.shared.1.notify_one();
+        }
+    }
+
+    /// Latest figures per target, in configured order. Never blocks on the network.
+    pub fn snapshot(&self) -> Vec<LatencyStats> {
+        let state = self.shared.0.lock().unwrap();
+        state
+            .config
+            .targets
+            .iter()
+            .filter_map(|t| state.history.get(t).map(|h| h.stats(t)))
+            .collect()
+    }
+}
+
+fn run(shared: Weak<Shared>) {
+    loop {
+        // Copy the config out so probing happens without the lock held
+        let Some(cfg) = shared.upgrade().map(|s| s.0.lock().unwrap().config.clone()) else {
+            return;
+        };
+        let started = Instant::now();
+
+        for target in &cfg.targets {
+            let sample = probe(target, Duration::from_millis(cfg.timeout_ms)).map(|d| d.as_secs_f32() * 1000.0);
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            let mut state = shared.0.lock().unwrap();
+            // Skip results for a target removed while it was being probed
+            if state.config.targets.contains(target) {
+                state.history.entry(target.clone()).or_default().push(sample, cfg.samples);
+            }
+        }
+
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        let interval = Duration::from_millis(cfg.interval_ms.max(100));
+        let state = shared.0.lock().unwrap();
+        if state.config == cfg {
+            let _ = shared.1.wait_timeout(state, interval.saturating_sub(started.elapsed()));
+        }
+    }
+}
+
+/// Time to complete a TCP handshake with `target` (`host:port`). Name
+/// resolution is not counted; each resolved address is tried in turn.
+pub fn probe(target: &str, timeout: Duration) -> Option<Duration> {
+    let addrs: Vec<SocketAddr> = target.to_socket_addrs().ok()?.collect();
+    addrs.iter().find_map(|addr| {
+        let start = Instant::now();
+        TcpStream::connect_timeout(addr, timeout).ok().map(|_| start.elapsed())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    #[test]
+    fn probe_connects_to_open_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        assert!(probe(&target, TIMEOUT).is_some());
+    }
+
+    #[test]
+    fn probe_fails_once_listener_is_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        assert!(probe(&target, TIMEOUT).is_none());
+    }
+
+    #[test]
+    fn probe_rejects_target_without_port() {
+        assert!(probe("127.0.0.1", TIMEOUT).is_none());
+    }
+
+    #[test]
+    fn history_stats_over_window() {
+        let mut history = TargetHistory::default();
+        for sample in [Some(10.0), None, Some(14.0), Some(12.0)] {
+            history.push(sample, 4);
+        }
+        let stats = history.stats("example.com:443");
+        assert_eq!(stats.current_ms, Some(12.0));
+        assert_eq!(stats.avg_ms, Some(12.0));
+        // |14 - 10| and |12 - 14| over two gaps
+        assert_eq!(stats.jitter_ms, Some(3.0));
+        assert_eq!(stats.failure_percent, 25.0);
+    }
+
+    #[test]
+    fn history_drops_samples_beyond_window() {
+        let mut history = TargetHistory::default();
+        for sample in [None, None, Some(5.0), Some(7.0)] {
+            history.push(sample, 2);
+        }
+        let stats = history.stats("example.com:443");
+        assert_eq!(stats.failure_percent, 0.0);
+        assert_eq!(stats.avg_ms, Some(6.0));
+    }
+}
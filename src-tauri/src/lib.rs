@@ -1,10 +1,27 @@
+mod battery;
 mod commands;
 mod config;
+mod cpu_times;
+mod cpufreq;
+#[cfg(test)]
+mod fixtures;
 mod gpu;
+mod hybrid;
+mod kernel_activity;
+mod latency;
+mod memory;
+mod metrics;
+mod net_quality;
+mod power;
+mod pressure;
+mod processes;
+mod rate;
+mod sampler;
+mod sensors;
 mod stats;
 
 use config::ConfigState;
-use stats::{StatsCollector, StatsState};
+use sampler::{MetricCatalog, StatsState, StatsSubscriptions};
 use std::sync::Mutex;
 use tauri::{
     menu::{MenuBuilder, MenuItemBuilder},
@@ -14,11 +31,16 @@ use tauri::{
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let cfg = config::load_config();
+    // Managed up front: windows can ask for the catalog before `setup` runs
+    let registry = stats::default_registry();
+    let catalog = MetricCatalog::new(&registry);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(ConfigState(Mutex::new(cfg)))
-        .manage(StatsState(Mutex::new(StatsCollector::new())))
+        .manage(StatsState::new())
+        .manage(StatsSubscriptions::new())
+        .manage(catalog)
         .setup(|app| {
             // Set up tray menu
             let settings_item = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
@@ -70,6 +92,9 @@ pub fn run() {
                 make_click_through(&overlay);
             }
 
+            // Collect stats on a background thread; it pushes each snapshot to subscribed windows
+            sampler::spawn(app.handle().clone(), registry);
+
             // Re-assert always-on-top every 500ms to stay above game windows
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
@@ -88,8 +113,20 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::get_config,
             commands::get_stats,
+            commands::subscribe_stats,
+            commands::unsubscribe_stats,
+            commands::get_metric_catalog,
             commands::save_metric,
             commands::save_metrics_order,
+            commands::get_disk_devices,
+            commands::save_disk_device,
+            commands::save_filesystems,
+            commands::save_network,
+            commands::save_latency,
+            commands::save_power,
+            commands::save_sampling,
+            commands::save_processes,
+            commands::save_tracked_process,
             commands::save_appearance,
             commands::toggle_startup,
             commands::get_startup_status,
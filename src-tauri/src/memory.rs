@@ -0,0 +1,138 @@
+/// Memory figures sysinfo doesn't expose. All values are in bytes; `None`
+/// means the platform has no equivalent.
+#[derive(Default)]
+pub struct MemoryDetails {
+    pub available: Option<u64>,
+    pub cached: Option<u64>,
+    /// Memory promised to processes (Committed_AS / commit charge)
+    pub committed: Option<u64>,
+    pub commit_limit: Option<u64>,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /proc/meminfo
+// Lines look like `Committed_AS:    8123456 kB`; every value we read is in kB.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct MemoryMonitor {
+    meminfo: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl MemoryMonitor {
+    pub fn new() -> Self {
+        Self::with_proc_root("/proc")
+    }
+
+    /// Reads `<proc_root>/meminfo` instead of the live file.
+    pub fn with_proc_root(proc_root: impl Into<std::path::PathBuf>) -> Self {
+        Self { meminfo: proc_root.into().join("meminfo") }
+    }
+
+    pub fn refresh(&mut self) -> MemoryDetails {
+        let Ok(content) = std::fs::read_to_string(&self.meminfo) else {
+            return MemoryDetails::default();
+        };
+
+        let field = |key: &str| -> Option<u64> {
+            content.lines().find_map(|line| {
+                let (name, rest) = line.split_once(':')?;
+                if name != key {
+                    return None;
+                }
+                let kb = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+                Some(kb * 1024)
+            })
+        };
+
+        MemoryDetails {
+            available: field("MemAvailable"),
+            cached: field("Cached"),
+            committed: field("Committed_AS"),
+            commit_limit: field("CommitLimit"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Windows: GetPerformanceInfo — values are in pages of `PageSize` bytes
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "windows")]
+pub struct MemoryMonitor;
+
+#[cfg(target_os = "windows")]
+impl MemoryMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> MemoryDetails {
+        use windows_sys::Win32::System::ProcessStatus::{GetPerformanceInfo, PERFORMANCE_INFORMATION};
+
+        let mut info: PERFORMANCE_INFORMATION = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<PERFORMANCE_INFORMATION>() as u32;
+        if unsafe { GetPerformanceInfo(&mut info, size) } == 0 {
+            return MemoryDetails::default();
+        }
+
+        let pages = |n: usize| Some(n as u64 * info.PageSize as u64);
+        MemoryDetails {
+            available: pages(info.PhysicalAvailable),
+            cached: pages(info.SystemCache),
+            committed: pages(info.CommitTotal),
+            commit_limit: pages(info.CommitLimit),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub struct MemoryMonitor;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl MemoryMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> MemoryDetails {
+        MemoryDetails::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    #[test]
+    fn reads_meminfo_fields_in_bytes() {
+        let proc_root = FixtureDir::new();
+        proc_root.write(
+            "meminfo",
+            "MemTotal:       16000000 kB
+MemFree:         1000000 kB
+MemAvailable:    8000000 kB
+Buffers:          200000 kB
+SwapCached:         1234 kB
+Cached:          4000000 kB
+CommitLimit:    12000000 kB
+Committed_AS:    9000000 kB
+",
+        );
+        let mem = MemoryMonitor::with_proc_root(proc_root.path()).refresh();
+        assert_eq!(mem.available, Some(8_000_000 * 1024));
+        // Not confused with SwapCached
+        assert_eq!(mem.cached, Some(4_000_000 * 1024));
+        assert_eq!(mem.committed, Some(9_000_000 * 1024));
+        assert_eq!(mem.commit_limit, Some(12_000_000 * 1024));
+    }
+
+    #[test]
+    fn fields_missing_on_older_kernels_are_none() {
+        let proc_root = FixtureDir::new();
+        proc_root.write("meminfo", "MemTotal:       16000000 kB\nCached:          4000000 kB\n");
+        let mem = MemoryMonitor::with_proc_root(proc_root.path()).refresh();
+        assert_eq!(mem.available, None);
+        assert_eq!(mem.cached, Some(4_000_000 * 1024));
+    }
+}
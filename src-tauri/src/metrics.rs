@@ -0,0 +1,401 @@
+use crate::config::AppConfig;
+use crate::rate::{Clock, SystemClock};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Sources that are refreshed together, each group on its own interval
+/// (see `SamplingConfig`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MetricGroup {
+    Cpu,
+    Memory,
+    Gpu,
+    Power,
+    Disk,
+    Filesystems,
+    Network,
+    Sensors,
+    System,
+    Processes,
+    Battery,
+}
+
+impl MetricGroup {
+    pub const ALL: [MetricGroup; 11] = [
+        MetricGroup::Cpu,
+        MetricGroup::Memory,
+        MetricGroup::Gpu,
+        MetricGroup::Power,
+        MetricGroup::Disk,
+        MetricGroup::Filesystems,
+        MetricGroup::Network,
+        MetricGroup::Sensors,
+        MetricGroup::System,
+        MetricGroup::Processes,
+        MetricGroup::Battery,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            MetricGroup::Cpu => "cpu",
+            MetricGroup::Memory => "memory",
+            MetricGroup::Gpu => "gpu",
+            MetricGroup::Power => "power",
+            MetricGroup::Disk => "disk",
+            MetricGroup::Filesystems => "filesystems",
+            MetricGroup::Network => "network",
+            MetricGroup::Sensors => "sensors",
+            MetricGroup::System => "system",
+            MetricGroup::Processes => "processes",
+            MetricGroup::Battery => "battery",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|g| g.key() == key)
+    }
+}
+
+impl Serialize for MetricGroup {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+/// Unit of a metric, serialized as the suffix the overlay prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Unit {
+    #[serde(rename = "%")]
+    Percent,
+    #[serde(rename = "GHz")]
+    Ghz,
+    #[serde(rename = "MHz")]
+    Mhz,
+    #[serde(rename = "°C")]
+    Celsius,
+    #[serde(rename = "W")]
+    Watts,
+    #[serde(rename = "Wh")]
+    WattHours,
+    #[serde(rename = "GB")]
+    Gigabytes,
+    #[serde(rename = "MB")]
+    Megabytes,
+    #[serde(rename = "MB/s")]
+    MegabytesPerSec,
+    #[serde(rename = "/s")]
+    PerSec,
+    #[serde(rename = "min")]
+    Minutes,
+    #[serde(rename = "RPM")]
+    Rpm,
+    /// Counts, ratios, text and structured values
+    #[serde(rename = "")]
+    None,
+}
+
+/// A metric a source can emit: its stable ID (the key in `SystemStats`) and unit.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricDef {
+    pub id: &'static str,
+    pub unit: Unit,
+}
+
+impl MetricDef {
+    pub const fn new(id: &'static str, unit: Unit) -> Self {
+        Self { id, unit }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum MetricValue {
+    /// Not available on this machine (no sensor, no NVIDIA GPU, not Linux...)
+    Missing,
+    Number(f64),
+    Flag(bool),
+    Text(String),
+    /// One value per core, in OS order
+    Series(Vec<f64>),
+    /// Records with several fields each: per-disk, per-interface, processes...
+    Detail(serde_json::Value),
+}
+
+impl MetricValue {
+    pub fn detail(value: &impl Serialize) -> Self {
+        serde_json::to_value(value).map_or(MetricValue::Missing, MetricValue::Detail)
+    }
+}
+
+impl From<f64> for MetricValue {
+    fn from(v: f64) -> Self {
+        MetricValue::Number(v)
+    }
+}
+
+impl From<f32> for MetricValue {
+    fn from(v: f32) -> Self {
+        MetricValue::Number(v as f64)
+    }
+}
+
+impl From<u32> for MetricValue {
+    fn from(v: u32) -> Self {
+        MetricValue::Number(v as f64)
+    }
+}
+
+impl From<u64> for MetricValue {
+    fn from(v: u64) -> Self {
+        MetricValue::Number(v as f64)
+    }
+}
+
+impl From<bool> for MetricValue {
+    fn from(v: bool) -> Self {
+        MetricValue::Flag(v)
+    }
+}
+
+impl From<String> for MetricValue {
+    fn from(v: String) -> Self {
+        MetricValue::Text(v)
+    }
+}
+
+impl From<Vec<f32>> for MetricValue {
+    fn from(v: Vec<f32>) -> Self {
+        MetricValue::Series(v.into_iter().map(f64::from).collect())
+    }
+}
+
+impl From<Vec<u64>> for MetricValue {
+    fn from(v: Vec<u64>) -> Self {
+        MetricValue::Series(v.into_iter().map(|x| x as f64).collect())
+    }
+}
+
+impl<T: Into<MetricValue>> From<Option<T>> for MetricValue {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(MetricValue::Missing, Into::into)
+    }
+}
+
+/// Latest value of every metric, keyed by metric ID. Serializes as a flat
+/// object (`{"cpu_percent": 12.5, ...}`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SystemStats(HashMap<&'static str, MetricValue>);
+
+impl SystemStats {
+    pub fn set(&mut self, id: &'static str, value: impl Into<MetricValue>) {
+        self.0.insert(id, value.into());
+    }
+
+    pub fn get(&self, id: &str) -> Option<&MetricValue> {
+        self.0.get(id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<MetricValue> {
+        self.0.remove(id)
+    }
+
+    pub fn number(&self, id: &str) -> Option<f64> {
+        match self.get(id) {
+            Some(MetricValue::Number(n)) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// What a source gets on each call: the time, the config and which metric IDs
+/// the shown metrics need.
+pub struct SourceContext<'a> {
+    pub now: Instant,
+    pub cfg: &'a AppConfig,
+    wanted: &'a HashSet<&'static str>,
+}
+
+impl SourceContext<'_> {
+    pub fn wants(&self, id: &str) -> bool {
+        self.wanted.contains(id)
+    }
+
+    pub fn wants_any(&self, ids: &[&str]) -> bool {
+        ids.iter().any(|id| self.wants(id))
+    }
+}
+
+/// Something that produces metrics: a sysinfo view, a sysfs reader, NVML...
+/// Adding a metric means implementing this (or extending a source's `metrics()`),
+/// registering the source, and listing the metric in `METRIC_TOGGLES`.
+pub trait MetricSource: Send {
+    /// The source is refreshed on this group's interval.
+    fn group(&self) -> MetricGroup;
+
+    /// Every metric this source emits.
+    fn metrics(&self) -> &'static [MetricDef];
+
+    /// Called on every sampler pass, whether or not any of the source's
+    /// metrics is shown, for sources with background work to start or stop.
+    fn configure(&mut self, _ctx: &SourceContext) {}
+
+    /// Called once none of `metrics()` is wanted any more. Sources keeping
+    /// previous counters or timestamps drop them here, so the first values
+    /// after being shown again cover one interval rather than the hidden gap.
+    fn reset(&mut self) {}
+
+    /// Writes current values into `out`. Only called while at least one of
+    /// `metrics()` is wanted; may skip work for the ones that aren't. `out`
+    /// also holds the latest values of every other source.
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats);
+}
+
+/// Owns the registered sources and the merged result of their refreshes.
+pub struct MetricRegistry {
+    clock: Box<dyn Clock>,
+    sources: Vec<Box<dyn MetricSource>>,
+    wanted: HashSet<&'static str>,
+    stats: SystemStats,
+}
+
+impl MetricRegistry {
+    pub fn new() -> Self {
+        Self::with_clock(Box::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: Box<dyn Clock>) -> Self {
+        Self {
+            clock,
+            sources: Vec::new(),
+            wanted: HashSet::new(),
+            stats: SystemStats::default(),
+        }
+    }
+
+    pub fn register(&mut self, source: Box<dyn MetricSource>) {
+        self.sources.push(source);
+    }
+
+    /// Every metric of every registered source, in registration order.
+    pub fn metrics(&self) -> Vec<MetricDef> {
+        self.sources.iter().flat_map(|s| s.metrics().iter().copied()).collect()
+    }
+
+    /// Latest values of every source, each as of its own last refresh.
+    pub fn stats(&self) -> &SystemStats {
+        &self.stats
+    }
+
+    /// Applies the config: works out which metrics are wanted and lets every
+    /// source react. Call before `uses`/`refresh` whenever the config may have changed.
+    /// A source that is no longer wanted is reset and its values removed, so
+    /// nothing (including other sources reading `out`) sees them frozen.
+    pub fn configure(&mut self, cfg: &AppConfig) {
+        self.wanted = cfg.metrics.wanted();
+        let ctx = SourceContext {
+            now: self.clock.now(),
+            cfg,
+            wanted: &self.wanted,
+        };
+        for source in &mut self.sources {
+            source.configure(&ctx);
+            let ids = source.metrics();
+            if ids.iter().any(|m| ctx.wants(m.id)) {
+                continue;
+            }
+            // Values present means it was refreshed since it was last reset
+            let mut had_values = false;
+            for m in ids {
+                had_values |= self.stats.remove(m.id).is_some();
+            }
+            if had_values {
+                source.reset();
+            }
+        }
+    }
+
+    /// Whether any source in `group` has a wanted metric; unused groups are not sampled.
+    pub fn uses(&self, group: MetricGroup) -> bool {
+        self.sources
+            .iter()
+            .filter(|s| s.group() == group)
+            .any(|s| s.metrics().iter().any(|m| self.wanted.contains(m.id)))
+    }
+
+    /// Refreshes the sources of one group in place; the other groups keep
+    /// their previous values. Rates cover the time since the group's last refresh.
+    pub fn refresh(&mut self, group: MetricGroup, cfg: &AppConfig) {
+        let ctx = SourceContext {
+            now: self.clock.now(),
+            cfg,
+            wanted: &self.wanted,
+        };
+        for source in &mut self.sources {
+            if source.group() == group && source.metrics().iter().any(|m| ctx.wants(m.id)) {
+                source.refresh(&ctx, &mut self.stats);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct FixedClock(Instant);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Instant {
+            self.0
+        }
+    }
+
+    const GPU_POWER: &[MetricDef] = &[MetricDef::new("gpu_power_w", Unit::Watts)];
+
+    struct FakeGpu {
+        resets: Arc<AtomicUsize>,
+    }
+
+    impl MetricSource for FakeGpu {
+        fn group(&self) -> MetricGroup {
+            MetricGroup::Gpu
+        }
+
+        fn metrics(&self) -> &'static [MetricDef] {
+            GPU_POWER
+        }
+
+        fn reset(&mut self) {
+            self.resets.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+            out.set("gpu_power_w", 120.0);
+        }
+    }
+
+    #[test]
+    fn hidden_source_is_cleared_and_reset_once() {
+        let resets = Arc::new(AtomicUsize::new(0));
+        let mut registry = MetricRegistry::with_clock(Box::new(FixedClock(Instant::now())));
+        registry.register(Box::new(FakeGpu { resets: resets.clone() }));
+
+        let mut cfg = AppConfig::default();
+        cfg.metrics.set("session_energy", false);
+        cfg.metrics.set("gpu_power", true);
+        registry.configure(&cfg);
+        assert!(registry.uses(MetricGroup::Gpu));
+        registry.refresh(MetricGroup::Gpu, &cfg);
+        assert_eq!(registry.stats().number("gpu_power_w"), Some(120.0));
+
+        cfg.metrics.set("gpu_power", false);
+        registry.configure(&cfg);
+        registry.configure(&cfg);
+        assert!(!registry.uses(MetricGroup::Gpu));
+        assert!(registry.stats().get("gpu_power_w").is_none());
+        assert_eq!(resets.load(Ordering::Relaxed), 1);
+    }
+}
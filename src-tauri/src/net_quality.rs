@@ -0,0 +1,288 @@
+use crate::rate::RateCounter;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Error and drop rates of one interface, per second
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceQuality {
+    pub rx_errors: f32,
+    pub tx_errors: f32,
+    pub rx_drops: f32,
+    pub tx_drops: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NetQuality {
+    /// TCP segments retransmitted per second
+    pub tcp_retrans_per_sec: Option<f32>,
+    /// Retransmitted share of outgoing segments over the last window
+    pub tcp_retrans_percent: Option<f32>,
+    /// Connections currently in ESTABLISHED or CLOSE-WAIT
+    pub tcp_established: Option<u32>,
+    pub interfaces: HashMap<String, InterfaceQuality>,
+}
+
+/// Raw cumulative counters as read from the OS
+#[derive(Default)]
+struct Counters {
+    retrans_segs: Option<u64>,
+    out_segs: Option<u64>,
+    curr_estab: Option<u32>,
+    /// rx errors, tx errors, rx drops, tx drops
+    interfaces: HashMap<String, [u64; 4]>,
+}
+
+/// Turns the kernel's cumulative TCP and per-interface error counters into
+/// rates. The platform backends below only read the raw counters.
+pub struct NetQualityMonitor {
+    source: CounterSource,
+    retrans: RateCounter,
+    out_segs: RateCounter,
+    interfaces: HashMap<String, [RateCounter; 4]>,
+}
+
+impl NetQualityMonitor {
+    pub fn new() -> Self {
+        Self::with_source(CounterSource::new())
+    }
+
+    fn with_source(source: CounterSource) -> Self {
+        Self {
+            source,
+            retrans: RateCounter::default(),
+            out_segs: RateCounter::default(),
+            interfaces: HashMap::new(),
+        }
+    }
+
+    pub fn refresh(&mut self, now: Instant) -> NetQuality {
+        let counters = self.source.read();
+
+        let tcp_retrans_per_sec = counters.retrans_segs.map(|v| self.retrans.update(now, v));
+        let out_per_sec = counters.out_segs.map(|v| self.out_segs.update(now, v));
+        let tcp_retrans_percent = match (tcp_retrans_per_sec, out_per_sec) {
+            (Some(retrans), Some(out)) if out > 0.0 => Some((retrans / out * 100.0).min(100.0) as f32),
+            (Some(_), Some(_)) => Some(0.0),
+            _ => None,
+        };
+
+        self.interfaces.retain(|name, _| counters.interfaces.contains_key(name));
+        let interfaces = counters
+            .interfaces
+            .iter()
+            .map(|(name, values)| {
+                let rates = self.interfaces.entry(name.clone()).or_default();
+                let mut per_sec = [0.0_f32; 4];
+                for ((rate, counter), value) in per_sec.iter_mut().zip(rates.iter_mut()).zip(values) {
+                    *rate = counter.update(now, *value) as f32;
+                }
+                let quality = InterfaceQuality {
+                    rx_errors: per_sec[0],
+                    tx_errors: per_sec[1],
+                    rx_drops: per_sec[2],
+                    tx_drops: per_sec[3],
+                };
+                (name.clone(), quality)
+            })
+            .collect();
+
+        NetQuality {
+            tcp_retrans_per_sec: tcp_retrans_per_sec.map(|r| r as f32),
+            tcp_retrans_percent,
+            tcp_established: counters.curr_estab,
+            interfaces,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /proc/net/snmp and /proc/net/dev
+// snmp holds pairs of lines per protocol, a header and a value row:
+//   `Tcp: RtoAlgorithm ... CurrEstab InSegs OutSegs RetransSegs ...`
+//   `Tcp: 1 ... 2 5173 5171 0 ...`
+// dev has one row per interface after two header lines:
+//   `eth0: rx_bytes packets errs drop fifo frame compressed multicast tx_bytes packets errs drop ...`
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+struct CounterSource {
+    proc_root: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl CounterSource {
+    fn new() -> Self {
+        Self { proc_root: "/proc".into() }
+    }
+
+    fn read(&self) -> Counters {
+        let mut counters = Counters::default();
+
+        if let Ok(snmp) = std::fs::read_to_string(self.proc_root.join("net/snmp")) {
+            let tcp = parse_snmp_section(&snmp, "Tcp:");
+            counters.retrans_segs = tcp.get("RetransSegs").copied();
+            counters.out_segs = tcp.get("OutSegs").copied();
+            counters.curr_estab = tcp.get("CurrEstab").map(|&n| n as u32);
+        }
+
+        if let Ok(dev) = std::fs::read_to_string(self.proc_root.join("net/dev")) {
+            counters.interfaces = dev
+                .lines()
+                .skip(2)
+                .filter_map(|line| {
+                    let (name, rest) = line.split_once(':')?;
+                    let fields: Vec<u64> = rest.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+                    if fields.len() < 12 {
+                        return None;
+                    }
+                    Some((name.trim().to_string(), [fields[2], fields[10], fields[3], fields[11]]))
+                })
+                .collect();
+        }
+
+        counters
+    }
+}
+
+/// Zips a protocol's header row with its value row into name → value.
+#[cfg(target_os = "linux")]
+fn parse_snmp_section(content: &str, prefix: &str) -> HashMap<String, u64> {
+    let mut rows = content.lines().filter(|l| l.starts_with(prefix));
+    let (Some(header), Some(values)) = (rows.next(), rows.next()) else {
+        return HashMap::new();
+    };
+    header
+        .split_whitespace()
+        .skip(1)
+        .zip(values.split_whitespace().skip(1))
+        // MaxConn is -1; nothing we read is signed
+        .filter_map(|(name, value)| Some((name.to_string(), value.parse().ok()?)))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Windows: GetTcpStatistics and GetIfTable2 (keyed by Alias, like sysinfo)
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "windows")]
+struct CounterSource;
+
+#[cfg(target_os = "windows")]
+impl CounterSource {
+    fn new() -> Self {
+        Self
+    }
+
+    fn read(&self) -> Counters {
+        use windows_sys::Win32::NetworkManagement::IpHelper::{
+            FreeMibTable, GetIfTable2, GetTcpStatistics, MIB_IF_TABLE2, MIB_TCPSTATS_LH,
+        };
+
+        let mut counters = Counters::default();
+
+        let mut tcp: MIB_TCPSTATS_LH = unsafe { std::mem::zeroed() };
+        if unsafe { GetTcpStatistics(&mut tcp) } == 0 {
+            counters.retrans_segs = Some(tcp.dwRetransSegs as u64);
+            counters.out_segs = Some(tcp.dwOutSegs as u64);
+            counters.curr_estab = Some(tcp.dwCurrEstab);
+        }
+
+        let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+        if unsafe { GetIfTable2(&mut table) } == 0 {
+            let rows = unsafe { std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize) };
+            counters.interfaces = rows
+                .iter()
+                .map(|row| {
+                    let len = row.Alias.iter().position(|&c| c == 0).unwrap_or(row.Alias.len());
+                    let name = String::from_utf16_lossy(&row.Alias[..len]);
+                    (name, [row.InErrors, row.OutErrors, row.InDiscards, row.OutDiscards])
+                })
+                .collect();
+            unsafe { FreeMibTable(table as *const _) };
+        }
+
+        counters
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+struct CounterSource;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+impl CounterSource {
+    fn new() -> Self {
+        Self
+    }
+
+    fn read(&self) -> Counters {
+        Counters::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+    use std::time::Duration;
+
+    const SNMP_HEADER: &str = "Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails \
+EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors";
+    const DEV_HEADER: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+";
+
+    fn write_counters(proc_root: &FixtureDir, estab: u32, out_segs: u64, retrans: u64, eth0: [u64; 4]) {
+        let [rx_errs, tx_errs, rx_drop, tx_drop] = eth0;
+        proc_root
+            .write(
+                "net/snmp",
+                &format!(
+                    "Ip: Forwarding DefaultTTL\nIp: 1 64\n{}\nTcp: 1 200 120000 -1 10 5 0 0 {} 5000 {} {} 0 0 0\n",
+                    SNMP_HEADER, estab, out_segs, retrans
+                ),
+            )
+            .write(
+                "net/dev",
+                &format!(
+                    "{}    lo: 500 5 0 0 0 0 0 0 500 5 0 0 0 0 0 0\n  eth0: 1000 10 {} {} 0 0 0 0 2000 20 {} {} 0 0 0 0\n",
+                    DEV_HEADER, rx_errs, rx_drop, tx_errs, tx_drop
+                ),
+            );
+    }
+
+    #[test]
+    fn tcp_and_interface_rates() {
+        let proc_root = FixtureDir::new();
+        let mut monitor = NetQualityMonitor::with_source(CounterSource {
+            proc_root: proc_root.path().into(),
+        });
+        let t0 = Instant::now();
+
+        write_counters(&proc_root, 7, 1000, 10, [0, 0, 0, 0]);
+        let first = monitor.refresh(t0);
+        assert_eq!(first.tcp_established, Some(7));
+        assert_eq!(first.tcp_retrans_per_sec, Some(0.0));
+        assert_eq!(first.interfaces.len(), 2);
+
+        // Over two seconds: 2000 segments out, 40 of them retransmits
+        write_counters(&proc_root, 9, 3000, 50, [4, 2, 8, 6]);
+        let second = monitor.refresh(t0 + Duration::from_secs(2));
+        assert_eq!(second.tcp_established, Some(9));
+        assert_eq!(second.tcp_retrans_per_sec, Some(20.0));
+        assert_eq!(second.tcp_retrans_percent, Some(2.0));
+        let eth0 = &second.interfaces["eth0"];
+        assert_eq!((eth0.rx_errors, eth0.tx_errors), (2.0, 1.0));
+        assert_eq!((eth0.rx_drops, eth0.tx_drops), (4.0, 3.0));
+        assert_eq!(second.interfaces["lo"].rx_errors, 0.0);
+    }
+
+    #[test]
+    fn missing_files_leave_counters_empty() {
+        let proc_root = FixtureDir::new();
+        let mut monitor = NetQualityMonitor::with_source(CounterSource {
+            proc_root: proc_root.path().into(),
+        });
+        let quality = monitor.refresh(Instant::now());
+        assert_eq!(quality.tcp_retrans_per_sec, None);
+        assert!(quality.interfaces.is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use std::time::Instant;
+
+/// CPU power from RAPL energy counters, averaged over the last window.
+#[derive(Debug, Clone, Default)]
+pub struct CpuPower {
+    /// Sum of all packages (sockets)
+    pub package_w: Option<f32>,
+    pub dram_w: Option<f32>,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /sys/class/powercap/intel-rapl:* (also used by AMD since 5.8)
+// Each zone has `name` (package-0, dram, core, uncore, psys) and `energy_uj`,
+// a µJ counter that wraps at `max_energy_range_uj`. The core/uncore subzones
+// are part of their package, so only package-N and dram zones are read.
+// `intel-rapl-mmio:*` mirrors package-0 and is skipped to avoid counting it twice.
+// energy_uj is root-only on kernels patched for CVE-2020-8694; then all is None.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+struct RaplZone {
+    energy_file: std::path::PathBuf,
+    is_dram: bool,
+    max_range_uj: u64,
+    last: Option<(Instant, u64)>,
+}
+
+#[cfg(target_os = "linux")]
+pub struct RaplMonitor {
+    zones: Vec<RaplZone>,
+}
+
+#[cfg(target_os = "linux")]
+impl RaplMonitor {
+    pub fn new() -> Self {
+        Self::with_powercap_root("/sys/class/powercap")
+    }
+
+    /// Discovers zones under `root` instead of the live sysfs class.
+    pub fn with_powercap_root(root: impl AsRef<std::path::Path>) -> Self {
+        let Ok(entries) = std::fs::read_dir(root.as_ref()) else {
+            return Self { zones: Vec::new() };
+        };
+
+        let mut zones: Vec<RaplZone> = entries
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("intel-rapl:"))
+            .filter_map(|e| {
+                let dir = e.path();
+                let name = std::fs::read_to_string(dir.join("name")).ok()?;
+                let name = name.trim();
+                let is_dram = name == "dram";
+                if !is_dram && !name.starts_with("package-") {
+                    return None;
+                }
+                let max_range_uj = std::fs::read_to_string(dir.join("max_energy_range_uj"))
+                    .ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .unwrap_or(u64::MAX);
+                Some(RaplZone {
+                    energy_file: dir.join("energy_uj"),
+                    is_dram,
+                    max_range_uj,
+                    last: None,
+                })
+            })
+            .collect();
+        zones.sort_by(|a, b| a.energy_file.cmp(&b.energy_file));
+        Self { zones }
+    }
+
+    pub fn refresh(&mut self, now: Instant) -> CpuPower {
+        let mut package_w: Option<f32> = None;
+        let mut dram_w: Option<f32> = None;
+
+        for zone in &mut self.zones {
+            let Some(energy) = std::fs::read_to_string(&zone.energy_file)
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+            else {
+                continue;
+            };
+            let Some((then, prev)) = zone.last.replace((now, energy)) else {
+                continue;
+            };
+            let elapsed = now.saturating_duration_since(then).as_secs_f64();
+            if elapsed <= 0.0 {
+                continue;
+            }
+            // The counter wraps every few minutes under load on some CPUs
+            let delta_uj = if energy >= prev {
+                energy - prev
+            } else {
+                zone.max_range_uj.saturating_sub(prev) + energy
+            };
+            let watts = (delta_uj as f64 / 1e6 / elapsed) as f32;
+
+            let total = if zone.is_dram { &mut dram_w } else { &mut package_w };
+            *total = Some(total.unwrap_or(0.0) + watts);
+        }
+
+        CpuPower { package_w, dram_w }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct RaplMonitor;
+
+#[cfg(not(target_os = "linux"))]
+impl RaplMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self, _now: Instant) -> CpuPower {
+        CpuPower::default()
+    }
+}
+
+/// Integrates power over time into the energy used across `update` calls;
+/// time between a `pause` and the next `update` isn't counted.
+#[derive(Debug, Default)]
+pub struct EnergyMeter {
+    last: Option<Instant>,
+    wh: f64,
+}
+
+impl EnergyMeter {
+    /// Adds `watts` (the average since the previous call) and returns the
+    /// session total in Wh.
+    pub fn update(&mut self, now: Instant, watts: f64) -> f64 {
+        if let Some(then) = self.last {
+            let hours = now.saturating_duration_since(then).as_secs_f64() / 3600.0;
+            self.wh += watts * hours;
+        }
+        self.last = Some(now);
+        self.wh
+    }
+
+    /// Stops counting until the next `update`, which then only starts a new
+    /// window; the session total is kept.
+    pub fn pause(&mut self) {
+        self.last = None;
+    }
+
+    pub fn wh(&self) -> f64 {
+        self.wh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn energy_meter_integrates_watts() {
+        let t0 = Instant::now();
+        let mut meter = EnergyMeter::default();
+        assert_eq!(meter.update(t0, 30.0), 0.0);
+        // 30 W for two minutes is 1 Wh
+        assert_eq!(meter.update(t0 + Duration::from_secs(120), 30.0), 1.0);
+        assert_eq!(meter.update(t0 + Duration::from_secs(240), 60.0), 3.0);
+    }
+
+    #[test]
+    fn paused_time_is_not_counted() {
+        let t0 = Instant::now();
+        let mut meter = EnergyMeter::default();
+        meter.update(t0, 30.0);
+        meter.update(t0 + Duration::from_secs(120), 30.0);
+        meter.pause();
+        assert_eq!(meter.update(t0 + Duration::from_secs(3600), 30.0), 1.0);
+        assert_eq!(meter.update(t0 + Duration::from_secs(3720), 30.0), 2.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    mod rapl {
+        use super::*;
+        use crate::fixtures::FixtureDir;
+
+        fn zone(powercap: &FixtureDir, dir: &str, name: &str, energy_uj: u64) {
+            powercap
+                .write(&format!("{}/name", dir), &format!("{}\n", name))
+                .write(&format!("{}/energy_uj", dir), &format!("{}\n", energy_uj))
+                .write(&format!("{}/max_energy_range_uj", dir), "10000000\n");
+        }
+
+        fn set_energy(powercap: &FixtureDir, dir: &str, energy_uj: u64) {
+            powercap.write(&format!("{}/energy_uj", dir), &format!("{}\n", energy_uj));
+        }
+
+        #[test]
+        fn sums_packages_and_dram_skipping_subzones() {
+            let powercap = FixtureDir::new();
+            zone(&powercap, "intel-rapl:0", "package-0", 0);
+            zone(&powercap, "intel-rapl:1", "package-1", 0);
+            zone(&powercap, "intel-rapl:0:0", "core", 0);
+            zone(&powercap, "intel-rapl:0:1", "uncore", 0);
+            zone(&powercap, "intel-rapl:0:2", "dram", 0);
+            zone(&powercap, "intel-rapl-mmio:0", "package-0", 0);
+
+            let mut rapl = RaplMonitor::with_powercap_root(powercap.path());
+            let t0 = Instant::now();
+            let first = rapl.refresh(t0);
+            assert_eq!((first.package_w, first.dram_w), (None, None));
+
+            set_energy(&powercap, "intel-rapl:0", 4_000_000);
+            set_energy(&powercap, "intel-rapl:1", 2_000_000);
+            set_energy(&powercap, "intel-rapl:0:0", 9_000_000);
+            set_energy(&powercap, "intel-rapl:0:1", 9_000_000);
+            set_energy(&powercap, "intel-rapl:0:2", 1_000_000);
+            set_energy(&powercap, "intel-rapl-mmio:0", 4_000_000);
+            let power = rapl.refresh(t0 + Duration::from_secs(2));
+            assert_eq!(power.package_w, Some(3.0));
+            assert_eq!(power.dram_w, Some(0.5));
+        }
+
+        #[test]
+        fn counter_wraps_at_max_energy_range() {
+            let powercap = FixtureDir::new();
+            zone(&powercap, "intel-rapl:0", "package-0", 9_000_000);
+
+            let mut rapl = RaplMonitor::with_powercap_root(powercap.path());
+            let t0 = Instant::now();
+            rapl.refresh(t0);
+            // 1 J up to the 10 J range, then 2 J past the wrap
+            set_energy(&powercap, "intel-rapl:0", 2_000_000);
+            assert_eq!(rapl.refresh(t0 + Duration::from_secs(1)).package_w, Some(3.0));
+        }
+    }
+}
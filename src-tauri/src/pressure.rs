@@ -0,0 +1,106 @@
+/// Pressure stall information: share of the last 10 seconds in which some
+/// (at least one) or all non-idle tasks were stalled on a resource.
+#[derive(Debug, Clone, Default)]
+pub struct Pressure {
+    pub some_avg10: f32,
+    /// Not reported for `cpu` by kernels older than 5.13
+    pub full_avg10: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PressureStats {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /proc/pressure/{cpu,memory,io} (kernel 4.20+, CONFIG_PSI)
+// `some avg10=1.23 avg60=0.80 avg300=0.20 total=123456`
+// `full avg10=0.00 avg60=0.00 avg300=0.00 total=0`
+// The kernel already averages these, so no deltas are needed here.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct PressureMonitor {
+    pressure_dir: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl PressureMonitor {
+    pub fn new() -> Self {
+        Self::with_proc_root("/proc")
+    }
+
+    /// Reads `<proc_root>/pressure/*` instead of the live files.
+    pub fn with_proc_root(proc_root: impl Into<std::path::PathBuf>) -> Self {
+        Self { pressure_dir: proc_root.into().join("pressure") }
+    }
+
+    pub fn refresh(&mut self) -> PressureStats {
+        let read = |resource: &str| {
+            std::fs::read_to_string(self.pressure_dir.join(resource))
+                .ok()
+                .and_then(|content| parse_pressure(&content))
+        };
+        PressureStats {
+            cpu: read("cpu"),
+            memory: read("memory"),
+            io: read("io"),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn parse_pressure(content: &str) -> Option<Pressure> {
+    let avg10 = |kind: &str| -> Option<f32> {
+        let line = content.lines().find(|l| l.starts_with(kind))?;
+        line.split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    };
+    Some(Pressure {
+        some_avg10: avg10("some ")?,
+        full_avg10: avg10("full "),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct PressureMonitor;
+
+#[cfg(not(target_os = "linux"))]
+impl PressureMonitor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self) -> PressureStats {
+        PressureStats::default()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    #[test]
+    fn reads_avg10_per_resource() {
+        let proc_root = FixtureDir::new();
+        proc_root
+            // Pre-5.13 kernels have no `full` line for cpu
+            .write("pressure/cpu", "some avg10=1.23 avg60=0.80 avg300=0.20 total=123456\n")
+            .write(
+                "pressure/memory",
+                "some avg10=4.50 avg60=2.00 avg300=1.00 total=999\nfull avg10=2.25 avg60=1.00 avg300=0.50 total=500\n",
+            );
+        let psi = PressureMonitor::with_proc_root(proc_root.path()).refresh();
+
+        let cpu = psi.cpu.unwrap();
+        assert_eq!((cpu.some_avg10, cpu.full_avg10), (1.23, None));
+        let memory = psi.memory.unwrap();
+        assert_eq!((memory.some_avg10, memory.full_avg10), (4.5, Some(2.25)));
+        // No io file: kernel without CONFIG_PSI or a restricted container
+        assert!(psi.io.is_none());
+    }
+}
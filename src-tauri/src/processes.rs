@@ -0,0 +1,358 @@
+use crate::config::{ProcessSort, ProcessesConfig, TrackedProcessConfig};
+use crate::rate::RateCounter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Instant;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessEntry {
+    pub name: String,
+    /// Heaviest member when several processes are grouped
+    pub pid: u32,
+    /// CPU % of the whole machine, resident MB, or disk MB/s depending on `sort_by`
+    pub value: f64,
+    /// Number of processes folded into this entry
+    pub count: usize,
+}
+
+/// Ranks the processes in a `System` refreshed by the caller. Disk I/O is
+/// turned into MB/s per PID from sysinfo's cumulative byte totals.
+pub struct ProcessRanker {
+    disk_rates: HashMap<Pid, RateCounter>,
+}
+
+impl ProcessRanker {
+    pub fn new() -> Self {
+        Self { disk_rates: HashMap::new() }
+    }
+
+    pub fn top(&mut self, sys: &System, now: Instant, cfg: &ProcessesConfig) -> Vec<ProcessEntry> {
+        let cpu_count = sys.cpus().len().max(1) as f64;
+
+        // Linux lists threads as tasks of their process; they'd count twice
+        let processes: Vec<&Process> = sys.processes().values().filter(|p| p.thread_kind().is_none()).collect();
+
+        self.disk_rates.retain(|pid, _| sys.process(*pid).is_some());
+
+        let entries: Vec<ProcessEntry> = processes
+            .iter()
+            .map(|p| {
+                let value = match cfg.sort_by {
+                    // sysinfo reports % of one core; scale to the whole machine like Task Manager
+                    ProcessSort::Cpu => p.cpu_usage() as f64 / cpu_count,
+                    ProcessSort::Memory => p.memory() as f64 / 1_048_576.0,
+                    ProcessSort::Disk => {
+                        let usage = p.disk_usage();
+                        let total = usage.total_read_bytes + usage.total_written_bytes;
+                        self.disk_rates.entry(p.pid()).or_default().update(now, total) / 1_048_576.0
+                    }
+                };
+                ProcessEntry {
+                    name: p.name().to_string_lossy().into_owned(),
+                    pid: p.pid().as_u32(),
+                    value,
+                    count: 1,
+                }
+            })
+            .collect();
+
+        rank(entries, cfg)
+    }
+}
+
+/// Heaviest first, grouped by name if configured, cut to `cfg.count`.
+fn rank(mut entries: Vec<ProcessEntry>, cfg: &ProcessesConfig) -> Vec<ProcessEntry> {
+    if cfg.group_by_exe {
+        entries = group_by_name(entries);
+    }
+    entries.sort_by(|a, b| b.value.total_cmp(&a.value));
+    entries.truncate(cfg.count);
+    entries
+}
+
+/// Folds processes sharing an executable name (browser helpers, game
+/// launchers) into one entry carrying the PID of the heaviest member.
+fn group_by_name(entries: Vec<ProcessEntry>) -> Vec<ProcessEntry> {
+    let mut groups: HashMap<String, (ProcessEntry, f64)> = HashMap::new();
+    for entry in entries {
+        match groups.get_mut(&entry.name) {
+            Some((group, heaviest)) => {
+                if entry.value > *heaviest {
+                    *heaviest = entry.value;
+                    group.pid = entry.pid;
+                }
+                group.value += entry.value;
+                group.count += 1;
+            }
+            None => {
+                let value = entry.value;
+                groups.insert(entry.name.clone(), (entry, value));
+            }
+        }
+    }
+    groups.into_values().map(|(group, _)| group).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedProcessStats {
+    pub name: String,
+    pub pid: u32,
+    /// % of the whole machine, like the top-process ranking
+    pub cpu_percent: f32,
+    pub rss_mb: f64,
+    pub threads: Option<u32>,
+    /// Open handles on Windows, open file descriptors on Linux
+    pub handles: Option<u32>,
+    pub read_mb: f64,
+    pub write_mb: f64,
+}
+
+/// Follows one process by name or PID. When a process tracked by name exits,
+/// the next refresh looks it up again, so restarting the game re-attaches.
+pub struct ProcessTracker {
+    attached: Option<Pid>,
+    read: RateCounter,
+    write: RateCounter,
+}
+
+impl ProcessTracker {
+    pub fn new() -> Self {
+        Self {
+            attached: None,
+            read: RateCounter::default(),
+            write: RateCounter::default(),
+        }
+    }
+
+    pub fn refresh(&mut self, sys: &mut System, now: Instant, cfg: &TrackedProcessConfig) -> Option<TrackedProcessStats> {
+        if cfg.name.is_none() && cfg.pid.is_none() {
+            self.attached = None;
+            return None;
+        }
+
+        let kind = ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet);
+
+        // Stay attached while the process lives and still matches the target
+        if let Some(pid) = self.attached {
+            sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, kind);
+            if !sys.process(pid).is_some_and(|p| is_target(cfg, p)) {
+                self.attached = None;
+            }
+        }
+
+        if self.attached.is_none() {
+            sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+            // Several matches (launcher + game, browser helpers): follow the biggest
+            self.attached = sys
+                .processes()
+                .values()
+                .filter(|p| p.thread_kind().is_none() && is_target(cfg, p))
+                .max_by_key(|p| p.memory())
+                .map(|p| p.pid());
+            self.read = RateCounter::default();
+            self.write = RateCounter::default();
+        }
+
+        let process = sys.process(self.attached?)?;
+        let disk = process.disk_usage();
+        let (threads, handles) = thread_and_handle_counts(process.pid());
+
+        Some(TrackedProcessStats {
+            name: process.name().to_string_lossy().into_owned(),
+            pid: process.pid().as_u32(),
+            cpu_percent: process.cpu_usage() / sys.cpus().len().max(1) as f32,
+            rss_mb: process.memory() as f64 / 1_048_576.0,
+            threads,
+            handles,
+            read_mb: self.read.update(now, disk.total_read_bytes) / 1_048_576.0,
+            write_mb: self.write.update(now, disk.total_written_bytes) / 1_048_576.0,
+        })
+    }
+}
+
+fn is_target(cfg: &TrackedProcessConfig, process: &Process) -> bool {
+    let exe_name = process.exe().and_then(|e| e.file_name());
+    let names = [Some(process.name()), exe_name].into_iter().flatten().map(|n| n.to_string_lossy());
+    matches_target(cfg, process.pid().as_u32(), names)
+}
+
+/// A name matches the process name or executable file name, case-insensitively
+/// and with or without `.exe`; a name takes precedence over a PID.
+fn matches_target<S: AsRef<str>>(cfg: &TrackedProcessConfig, pid: u32, names: impl IntoIterator<Item = S>) -> bool {
+    match (&cfg.name, cfg.pid) {
+        (Some(name), _) => {
+            let wanted = name.to_lowercase();
+            let wanted = wanted.trim_end_matches(".exe");
+            names
+                .into_iter()
+                .any(|n| n.as_ref().to_lowercase().trim_end_matches(".exe") == wanted)
+        }
+        (None, Some(target)) => pid == target,
+        (None, None) => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Thread and handle counts — sysinfo exposes neither on every platform
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+fn thread_and_handle_counts(pid: Pid) -> (Option<u32>, Option<u32>) {
+    let dir = std::path::Path::new("/proc").join(pid.to_string());
+    let threads = std::fs::read_to_string(dir.join("status")).ok().and_then(|status| {
+        status
+            .lines()
+            .find_map(|l| l.strip_prefix("Threads:"))
+            .and_then(|n| n.trim().parse().ok())
+    });
+    // Listing fd/ of another user's process needs ptrace access; None then
+    let fds = std::fs::read_dir(dir.join("fd")).ok().map(|entries| entries.count() as u32);
+    (threads, fds)
+}
+
+#[cfg(target_os = "windows")]
+fn thread_and_handle_counts(pid: Pid) -> (Option<u32>, Option<u32>) {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::Threading::{GetProcessHandleCount, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    let pid = pid.as_u32();
+
+    let handles = unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            None
+        } else {
+            let mut count = 0u32;
+            let ok = GetProcessHandleCount(process, &mut count) != 0;
+            CloseHandle(process);
+            ok.then_some(count)
+        }
+    };
+
+    // The process snapshot carries a thread count per entry
+    let threads = unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            None
+        } else {
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            let mut found = None;
+            let mut ok = Process32FirstW(snapshot, &mut entry) != 0;
+            while ok {
+                if entry.th32ProcessID == pid {
+                    found = Some(entry.cntThreads);
+                    break;
+                }
+                ok = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+            found
+        }
+    };
+
+    (threads, handles)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn thread_and_handle_counts(_pid: Pid) -> (Option<u32>, Option<u32>) {
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, pid: u32, value: f64) -> ProcessEntry {
+        ProcessEntry { name: name.to_string(), pid, value, count: 1 }
+    }
+
+    fn summary(entries: &[ProcessEntry]) -> Vec<(&str, u32, f64, usize)> {
+        entries.iter().map(|e| (e.name.as_str(), e.pid, e.value, e.count)).collect()
+    }
+
+    #[test]
+    fn grouping_sums_values_and_keeps_heaviest_pid() {
+        let entries = vec![
+            entry("chrome", 10, 2.0),
+            entry("chrome", 11, 5.0),
+            entry("chrome", 12, 1.0),
+            entry("game", 20, 4.0),
+        ];
+        let mut grouped = group_by_name(entries);
+        grouped.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(summary(&grouped), [("chrome", 11, 8.0, 3), ("game", 20, 4.0, 1)]);
+    }
+
+    #[test]
+    fn ranking_sorts_after_grouping_then_truncates() {
+        let entries = vec![
+            entry("chrome", 10, 3.0),
+            entry("game", 20, 5.0),
+            entry("chrome", 11, 3.0),
+            entry("editor", 30, 1.0),
+        ];
+        let cfg = ProcessesConfig { count: 2, ..Default::default() };
+        // Grouped, chrome (6.0) overtakes game
+        assert_eq!(summary(&rank(entries.clone(), &cfg)), [("chrome", 10, 6.0, 2), ("game", 20, 5.0, 1)]);
+
+        let cfg = ProcessesConfig { count: 3, group_by_exe: false, ..Default::default() };
+        assert_eq!(
+            summary(&rank(entries, &cfg)),
+            [("game", 20, 5.0, 1), ("chrome", 10, 3.0, 1), ("chrome", 11, 3.0, 1)]
+        );
+    }
+
+    fn target(name: Option<&str>, pid: Option<u32>) -> TrackedProcessConfig {
+        TrackedProcessConfig { name: name.map(str::to_string), pid }
+    }
+
+    #[test]
+    fn name_matches_case_insensitively_with_or_without_exe() {
+        assert!(matches_target(&target(Some("game"), None), 1, ["Game.EXE"]));
+        assert!(matches_target(&target(Some("game.exe"), None), 1, ["Game.EXE"]));
+        assert!(matches_target(&target(Some("GAME.exe"), None), 1, ["game"]));
+        // Either the process name or the executable file name may match
+        assert!(matches_target(&target(Some("game"), None), 1, ["Main Thread", "game.exe"]));
+        assert!(!matches_target(&target(Some("game"), None), 1, ["gamebar.exe"]));
+    }
+
+    #[test]
+    fn name_takes_precedence_over_pid() {
+        assert!(!matches_target(&target(Some("game"), Some(42)), 42, ["editor"]));
+        assert!(matches_target(&target(Some("game"), Some(42)), 7, ["game"]));
+        assert!(matches_target(&target(None, Some(42)), 42, ["editor"]));
+        assert!(!matches_target(&target(None, None), 42, ["editor"]));
+    }
+
+    #[test]
+    fn tracker_detaches_and_reattaches() {
+        let own = std::process::id();
+        let mut sys = System::new();
+        let mut tracker = ProcessTracker::new();
+        let now = Instant::now();
+
+        let stats = tracker.refresh(&mut sys, now, &target(None, Some(own))).unwrap();
+        assert_eq!(stats.pid, own);
+        assert_eq!(tracker.attached, Some(Pid::from_u32(own)));
+
+        // The attached process no longer matches: dropped, and nothing else matches
+        assert!(tracker.refresh(&mut sys, now, &target(None, Some(u32::MAX))).is_none());
+        assert_eq!(tracker.attached, None);
+
+        let exe = std::env::current_exe().unwrap();
+        let name = exe.file_name().unwrap().to_string_lossy().to_uppercase();
+        let stats = tracker.refresh(&mut sys, now, &target(Some(&name), None)).unwrap();
+        assert_eq!(stats.pid, own);
+
+        assert!(tracker.refresh(&mut sys, now, &target(None, None)).is_none());
+        assert_eq!(tracker.attached, None);
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+/// Samples closer together than this keep the previous rate instead of
+/// computing one over a window too short to be meaningful (e.g. two windows
+/// calling `get_stats` back to back).
+const MIN_WINDOW: Duration = Duration::from_millis(100);
+
+/// Time source for rate computation. Collectors take `now` from here instead of
+/// calling `Instant::now()`, so rate math can be driven by a fake clock.
+pub trait Clock: Send {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Turns a cumulative counter (bytes, sectors, ticks...) into a per-second rate
+/// using the real time elapsed between samples.
+#[derive(Debug, Default, Clone)]
+pub struct RateCounter {
+    last: Option<(Instant, u64)>,
+    rate: f64,
+}
+
+impl RateCounter {
+    /// Feeds a new counter value and returns units per second. The first sample
+    /// only primes the counter and returns 0; a counter that goes backwards
+    /// (reset, wrap, device replaced) also yields 0 for that window.
+    pub fn update(&mut self, now: Instant, value: u64) -> f64 {
+        match self.last {
+            Some((then, prev)) => {
+                let elapsed = now.saturating_duration_since(then);
+                if elapsed < MIN_WINDOW {
+                    return self.rate;
+                }
+                self.rate = value.saturating_sub(prev) as f64 / elapsed.as_secs_f64();
+            }
+            None => self.rate = 0.0,
+        }
+        self.last = Some((now, value));
+        self.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_primes_to_zero() {
+        let mut counter = RateCounter::default();
+        assert_eq!(counter.update(Instant::now(), 5_000), 0.0);
+    }
+
+    #[test]
+    fn rate_is_delta_over_elapsed_time() {
+        let t0 = Instant::now();
+        let mut counter = RateCounter::default();
+        counter.update(t0, 1_000);
+        assert_eq!(counter.update(t0 + Duration::from_secs(2), 1_200), 100.0);
+    }
+
+    #[test]
+    fn short_window_keeps_previous_rate() {
+        let t0 = Instant::now();
+        let mut counter = RateCounter::default();
+        counter.update(t0, 1_000);
+        counter.update(t0 + Duration::from_secs(2), 1_200);
+        let rate = counter.update(t0 + Duration::from_secs(2) + MIN_WINDOW / 2, 9_999);
+        assert_eq!(rate, 100.0);
+    }
+
+    #[test]
+    fn counter_going_backwards_yields_zero() {
+        let t0 = Instant::now();
+        let mut counter = RateCounter::default();
+        counter.update(t0, 1_000);
+        assert_eq!(counter.update(t0 + Duration::from_secs(1), 10), 0.0);
+    }
+}
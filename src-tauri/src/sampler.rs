@@ -0,0 +1,166 @@
+use crate::config::{ConfigState, MetricToggle, METRIC_TOGGLES};
+use crate::metrics::{MetricDef, MetricGroup, MetricRegistry, SystemStats};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Longest the thread sleeps between passes, so a metric enabled while every
+/// group is on a slow interval still shows up within a second.
+const MAX_SLEEP: Duration = Duration::from_secs(1);
+
+/// One complete sample. Published whole and never modified afterwards, so
+/// every reader of the same `seq` sees identical numbers.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct StatsSnapshot {
+    /// Increments with each sample; 0 until the first one is taken
+    pub seq: u64,
+    /// When the sample was taken, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// How long before `timestamp_ms` each metric group was last refreshed
+    pub ages_ms: BTreeMap<MetricGroup, u64>,
+    #[serde(flatten)]
+    pub stats: SystemStats,
+}
+
+/// Latest snapshot, swapped in by the sampler thread. Readers only clone the
+/// `Arc`, so they never wait on a collection in progress.
+pub struct StatsState(Mutex<Arc<StatsSnapshot>>);
+
+impl StatsState {
+    pub fn new() -> Self {
+        Self(Mutex::new(Arc::new(StatsSnapshot::default())))
+    }
+
+    pub fn latest(&self) -> Arc<StatsSnapshot> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn publish(&self, snapshot: StatsSnapshot) -> Arc<StatsSnapshot> {
+        let snapshot = Arc::new(snapshot);
+        *self.0.lock().unwrap() = snapshot.clone();
+        snapshot
+    }
+}
+
+struct Subscription {
+    interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+/// Windows that get `stats-updated` events, by label, each with the least
+/// time between two of its events.
+pub struct StatsSubscriptions(Mutex<HashMap<String, Subscription>>);
+
+impl StatsSubscriptions {
+    pub fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+
+    pub fn subscribe(&self, label: &str, interval: Duration) {
+        let mut subs = self.0.lock().unwrap();
+        let last_sent = subs.get(label).and_then(|s| s.last_sent);
+        subs.insert(label.to_string(), Subscription { interval, last_sent });
+    }
+
+    pub fn unsubscribe(&self, label: &str) {
+        self.0.lock().unwrap().remove(label);
+    }
+
+    /// Labels whose interval has passed since their last event, marked as sent.
+    fn due(&self, now: Instant) -> Vec<String> {
+        let mut subs = self.0.lock().unwrap();
+        subs.iter_mut()
+            .filter(|(_, s)| s.last_sent.is_none_or(|t| now.duration_since(t) >= s.interval))
+            .map(|(label, s)| {
+                s.last_sent = Some(now);
+                label.clone()
+            })
+            .collect()
+    }
+}
+
+/// What the frontends can show: the settings toggles and every metric the
+/// registered sources emit, with its unit.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricCatalog {
+    pub toggles: &'static [MetricToggle],
+    pub metrics: Vec<MetricDef>,
+}
+
+impl MetricCatalog {
+    pub fn new(registry: &MetricRegistry) -> Self {
+        Self {
+            toggles: METRIC_TOGGLES,
+            metrics: registry.metrics(),
+        }
+    }
+}
+
+/// Starts the thread that owns the `MetricRegistry`. Each metric group is
+/// refreshed on its own `sampling` interval; whenever any group ran, the merged
+/// result is published as a new snapshot and pushed to the subscribed windows.
+/// The thread is the only caller of `refresh()`, so delta-based rates always
+/// span one full group interval no matter how many windows read them.
+pub fn spawn(app: AppHandle, mut registry: MetricRegistry) {
+    std::thread::spawn(move || {
+        let mut last_run: HashMap<MetricGroup, Instant> = HashMap::new();
+        let mut seq = 0;
+        loop {
+            let cfg = app.state::<ConfigState>().0.lock().unwrap().clone();
+            let interval = |group| Duration::from_millis(cfg.sampling.interval_ms(group));
+
+            // Intervals and the shown metrics are re-read every pass, so a changed
+            // setting applies from the group's next run without restarting anything.
+            // Groups feeding no shown metric are not sampled at all.
+            registry.configure(&cfg);
+            let now = Instant::now();
+            let mut ran = false;
+            for group in MetricGroup::ALL {
+                if !registry.uses(group) {
+                    last_run.remove(&group);
+                } else if last_run.get(&group).is_none_or(|&t| now.duration_since(t) >= interval(group)) {
+                    registry.refresh(group, &cfg);
+                    last_run.insert(group, now);
+                    ran = true;
+                }
+            }
+
+            if ran {
+                seq += 1;
+                let published = Instant::now();
+                let ages_ms = last_run
+                    .iter()
+                    .map(|(&group, &t)| (group, published.duration_since(t).as_millis() as u64))
+                    .collect();
+                let timestamp_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64);
+                let snapshot = app.state::<StatsState>().publish(StatsSnapshot {
+                    seq,
+                    timestamp_ms,
+                    ages_ms,
+                    stats: registry.stats().clone(),
+                });
+                // Closed windows drop out instead of being sent to forever
+                let subscriptions = app.state::<StatsSubscriptions>();
+                for label in subscriptions.due(published) {
+                    if app.get_webview_window(&label).is_some() {
+                        let _ = app.emit_to(label.as_str(), "stats-updated", &*snapshot);
+                    } else {
+                        subscriptions.unsubscribe(&label);
+                    }
+                }
+            }
+
+            // Sleep until the next group is due
+            let next_due = last_run
+                .iter()
+                .map(|(&group, &t)| t + interval(group))
+                .min()
+                .map_or(now + MAX_SLEEP, |due| due.min(now + MAX_SLEEP));
+            std::thread::sleep(next_due.saturating_duration_since(Instant::now()));
+        }
+    });
+}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct FanReading {
+    pub label: String,
+    pub rpm: u32,
+}
+
+#[derive(Default)]
+pub struct SensorStats {
+    /// CPU package temperature (Tctl/Tdie on AMD); hottest core if no package sensor
+    pub cpu_temp: Option<f32>,
+    pub cpu_core_temps: Vec<f32>,
+    /// Hottest NVMe drive
+    pub nvme_temp: Option<f32>,
+    pub chipset_temp: Option<f32>,
+    /// Spinning fans only; headers reading 0 RPM are usually unpopulated
+    pub fans: Vec<FanReading>,
+}
+
+enum SensorKind {
+    CpuPackage,
+    CpuCore,
+    Nvme,
+    Chipset,
+}
+
+/// Maps a chip/label pair to what it measures. Chip names come from hwmon
+/// (`coretemp`, `k10temp`, `nvme`, `pch_*`); on other platforms only the label
+/// is known and `chip` is empty.
+fn classify(chip: &str, label: &str) -> Option<SensorKind> {
+    let chip = chip.to_lowercase();
+    let label = label.to_lowercase();
+
+    if chip == "nvme" || label.contains("nvme") {
+        return Some(SensorKind::Nvme);
+    }
+    if chip.starts_with("pch_") || label.contains("pch") || label.contains("chipset") {
+        return Some(SensorKind::Chipset);
+    }
+    // Motherboard chips also have "CPUTIN"-style sensors next to the socket;
+    // only trust a "cpu" label when there is no chip name to go by
+    let cpu_chip = matches!(chip.as_str(), "coretemp" | "k10temp" | "zenpower" | "cpu_thermal");
+    if cpu_chip || (chip.is_empty() && label.contains("cpu")) {
+        if label.starts_with("core") || label.starts_with("tccd") {
+            return Some(SensorKind::CpuCore);
+        }
+        return Some(SensorKind::CpuPackage);
+    }
+    if label.starts_with("package id") || label == "tctl" || label == "tdie" {
+        return Some(SensorKind::CpuPackage);
+    }
+    None
+}
+
+impl SensorStats {
+    fn record(&mut self, chip: &str, label: &str, celsius: f32) {
+        let max = |slot: &mut Option<f32>| *slot = Some(slot.map_or(celsius, |t| t.max(celsius)));
+        match classify(chip, label) {
+            // Tdie is Tctl without AMD's fan-curve offset, so prefer it when both exist
+            Some(SensorKind::CpuPackage) if label.eq_ignore_ascii_case("tdie") => self.cpu_temp = Some(celsius),
+            Some(SensorKind::CpuPackage) if label.eq_ignore_ascii_case("tctl") && self.cpu_temp.is_some() => {}
+            Some(SensorKind::CpuPackage) => max(&mut self.cpu_temp),
+            Some(SensorKind::CpuCore) => self.cpu_core_temps.push(celsius),
+            Some(SensorKind::Nvme) => max(&mut self.nvme_temp),
+            Some(SensorKind::Chipset) => max(&mut self.chipset_temp),
+            None => {}
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        if self.cpu_temp.is_none() {
+            self.cpu_temp = self.cpu_core_temps.iter().copied().reduce(f32::max);
+        }
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /sys/class/hwmon
+// Each hwmonN directory is one chip with a `name` file and numbered
+// tempN_input (millidegrees C) / fanN_input (RPM) files plus optional labels.
+// sysinfo's Components reads the same directory but exposes no fans.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+pub struct SensorMonitor {
+    hwmon_root: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl SensorMonitor {
+    pub fn new() -> Self {
+        Self::with_hwmon_root("/sys/class/hwmon")
+    }
+
+    /// Reads chips under `hwmon_root` instead of the live sysfs tree.
+    pub fn with_hwmon_root(hwmon_root: impl Into<std::path::PathBuf>) -> Self {
+        Self { hwmon_root: hwmon_root.into() }
+    }
+
+    pub fn refresh(&mut self) -> SensorStats {
+        use std::fs;
+
+        let mut stats = SensorStats::default();
+        let Ok(entries) = fs::read_dir(&self.hwmon_root) else {
+            return stats;
+        };
+        let mut chips: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        chips.sort();
+
+        let read = |path: std::path::PathBuf| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+
+        for chip_dir in chips {
+            let chip = read(chip_dir.join("name")).unwrap_or_default();
+            let Ok(files) = fs::read_dir(&chip_dir) else { continue };
+            let mut inputs: Vec<String> = files
+                .flatten()
+                .map(|f| f.file_name().to_string_lossy().into_owned())
+                .filter(|f| f.ends_with("_input") && (f.starts_with("temp") || f.starts_with("fan")))
+                .collect();
+            // Numeric order, so temp2 comes before temp10
+            let index = |f: &str| -> u32 {
+                f.trim_start_matches(char::is_alphabetic).trim_end_matches("_input").parse().unwrap_or(0)
+            };
+            inputs.sort_by_key(|f| (f.starts_with("fan"), index(f)));
+
+            for input in inputs {
+                let sensor = input.trim_end_matches("_input");
+                let Some(value) = read(chip_dir.join(&input)).and_then(|v| v.parse::<i64>().ok()) else {
+                    continue;
+                };
+                let label = read(chip_dir.join(format!("{}_label", sensor)))
+                    .unwrap_or_else(|| format!("{} {}", chip, sensor));
+
+                if sensor.starts_with("fan") {
+                    if value > 0 {
+                        stats.fans.push(FanReading { label, rpm: value as u32 });
+                    }
+                } else {
+                    stats.record(&chip, &label, value as f32 / 1000.0);
+                }
+            }
+        }
+        stats.finish()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Other platforms: sysinfo Components (temperatures only, labels vary by OS)
+// ---------------------------------------------------------------------------
+#[cfg(not(target_os = "linux"))]
+pub struct SensorMonitor {
+    components: sysinfo::Components,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl SensorMonitor {
+    pub fn new() -> Self {
+        Self { components: sysinfo::Components::new_with_refreshed_list() }
+    }
+
+    pub fn refresh(&mut self) -> SensorStats {
+        self.components.refresh(false);
+        let mut stats = SensorStats::default();
+        for component in self.components.list() {
+            if let Some(celsius) = component.temperature() {
+                stats.record("", component.label(), celsius);
+            }
+        }
+        stats.finish()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::FixtureDir;
+
+    fn refresh(hwmon: &FixtureDir) -> SensorStats {
+        SensorMonitor::with_hwmon_root(hwmon.path()).refresh()
+    }
+
+    #[test]
+    fn coretemp_package_and_cores() {
+        let hwmon = FixtureDir::new();
+        hwmon
+            .write("hwmon0/name", "coretemp\n")
+            .write("hwmon0/temp1_label", "Package id 0\n")
+            .write("hwmon0/temp1_input", "61000\n")
+            .write("hwmon0/temp2_label", "Core 0\n")
+            .write("hwmon0/temp2_input", "58000\n")
+            .write("hwmon0/temp10_label", "Core 8\n")
+            .write("hwmon0/temp10_input", "63000\n");
+        let stats = refresh(&hwmon);
+        assert_eq!(stats.cpu_temp, Some(61.0));
+        // temp2 before temp10
+        assert_eq!(stats.cpu_core_temps, [58.0, 63.0]);
+    }
+
+    #[test]
+    fn k10temp_prefers_tdie_over_tctl() {
+        for (tctl, tdie) in [("temp1", "temp2"), ("temp2", "temp1")] {
+            let hwmon = FixtureDir::new();
+            hwmon
+                .write("hwmon1/name", "k10temp\n")
+                .write(&format!("hwmon1/{}_label", tctl), "Tctl\n")
+                .write(&format!("hwmon1/{}_input", tctl), "75000\n")
+                .write(&format!("hwmon1/{}_label", tdie), "Tdie\n")
+                .write(&format!("hwmon1/{}_input", tdie), "65000\n");
+            assert_eq!(refresh(&hwmon).cpu_temp, Some(65.0));
+        }
+    }
+
+    #[test]
+    fn hottest_nvme_drive() {
+        let hwmon = FixtureDir::new();
+        hwmon
+            .write("hwmon2/name", "nvme\n")
+            .write("hwmon2/temp1_label", "Composite\n")
+            .write("hwmon2/temp1_input", "41850\n")
+            .write("hwmon3/name", "nvme\n")
+            .write("hwmon3/temp1_label", "Composite\n")
+            .write("hwmon3/temp1_input", "47850\n");
+        let stats = refresh(&hwmon);
+        assert_eq!(stats.nvme_temp, Some(47.85));
+        assert_eq!(stats.cpu_temp, None);
+    }
+
+    #[test]
+    fn stopped_fans_are_dropped() {
+        let hwmon = FixtureDir::new();
+        hwmon
+            .write("hwmon4/name", "nct6798\n")
+            .write("hwmon4/fan1_label", "CPU Fan\n")
+            .write("hwmon4/fan1_input", "1200\n")
+            .write("hwmon4/fan2_input", "0\n");
+        let fans = refresh(&hwmon).fans;
+        assert_eq!(fans.len(), 1);
+        assert_eq!((fans[0].label.as_str(), fans[0].rpm), ("CPU Fan", 1200));
+    }
+}
@@ -1,24 +1,65 @@
+use crate::battery::BatteryMonitor;
+use crate::config::{filesystem_metric_key, AppConfig, LatencyConfig};
+use crate::cpu_times::CpuTimeMonitor;
+use crate::cpufreq::CpuFreqMonitor;
+use crate::hybrid::{group_average, CoreTypes};
+use crate::kernel_activity::KernelActivityMonitor;
+use crate::latency::LatencyProbe;
+use crate::memory::{MemoryDetails, MemoryMonitor};
+use crate::metrics::{MetricDef, MetricGroup, MetricRegistry, MetricSource, MetricValue, SourceContext, SystemStats, Unit};
+use crate::net_quality::{NetQuality, NetQualityMonitor};
+use crate::power::{EnergyMeter, RaplMonitor};
+use crate::pressure::PressureMonitor;
+use crate::processes::{ProcessRanker, ProcessTracker};
+use crate::rate::RateCounter;
+use crate::sensors::SensorMonitor;
 use serde::Serialize;
-use std::sync::Mutex;
-use sysinfo::{Networks, System};
+use std::collections::HashMap;
+use std::time::Instant;
+use sysinfo::{
+    CpuRefreshKind, MemoryRefreshKind, Networks, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System,
+};
 
 #[derive(Debug, Clone, Serialize, Default)]
-pub struct SystemStats {
-    pub cpu_percent: f32,
-    pub cpu_freq_ghz: f32,
-    pub ram_percent: f32,
-    pub ram_used_gb: f32,
-    pub ram_total_gb: f32,
-    pub gpu_percent: Option<u32>,
-    pub gpu_temp: Option<u32>,
-    pub gpu_power_w: Option<u32>,
-    pub gpu_clock_mhz: Option<u32>,
-    pub vram_used_mb: Option<u32>,
-    pub vram_total_mb: Option<u32>,
-    pub disk_read_mb: f64,
-    pub disk_write_mb: f64,
-    pub net_down_mb: f64,
-    pub net_up_mb: f64,
+pub struct DiskStats {
+    pub name: String,
+    pub read_mb: f64,
+    pub write_mb: f64,
+    /// Utilization: share of time with at least one request in flight
+    pub busy_percent: f32,
+    /// Average time per completed request, queueing included
+    pub await_ms: f32,
+    /// Average number of requests waiting or in service
+    pub queue_depth: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct FilesystemStats {
+    /// As configured: `/home`, `C:`
+    pub mount: String,
+    pub used_gb: f64,
+    /// Space available to unprivileged users (excludes the root reserve on ext4)
+    pub free_gb: f64,
+    pub total_gb: f64,
+    pub used_percent: f32,
+    /// `None` where the filesystem has no fixed inode table (btrfs, NTFS...)
+    pub inodes_used_percent: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct NetInterfaceStats {
+    pub name: String,
+    pub down_mb: f64,
+    pub up_mb: f64,
+    /// Negotiated link speed, when the driver reports one
+    pub link_mbps: Option<u64>,
+    /// Busiest direction as a percentage of `link_mbps`
+    pub utilization_percent: Option<f32>,
+    /// Receive/transmit errors and drops per second
+    pub rx_errors_per_sec: f32,
+    pub tx_errors_per_sec: f32,
+    pub rx_drops_per_sec: f32,
+    pub tx_drops_per_sec: f32,
 }
 
 // ---------------------------------------------------------------------------
@@ -28,8 +69,10 @@ pub struct SystemStats {
 // ---------------------------------------------------------------------------
 #[cfg(target_os = "windows")]
 mod disk_pdh {
-    use std::mem::zeroed;
-    use std::ptr::null;
+    use super::DiskStats;
+    use std::collections::HashMap;
+    use std::mem::size_of;
+    use std::ptr::{null, null_mut};
 
     // PDH handles are isize on Windows (HANDLE = *mut c_void, but windows-sys does not
     // export PDH_HQUERY/PDH_HCOUNTER as types — we declare them manually.)
@@ -49,21 +92,32 @@ mod disk_pdh {
         pub value: PdhFmtAnon,
     }
 
+    // PDH_FMT_COUNTERVALUE_ITEM_W — one entry per instance of a wildcard counter
+    #[repr(C)]
+    struct PdhFmtCounterValueItem {
+        pub name: *const u16,
+        pub value: PdhFmtCounterValue,
+    }
+
     #[link(name = "pdh")]
     extern "system" {
         fn PdhOpenQueryW(src: *const u16, userdata: usize, query: *mut PdhHQuery) -> u32;
         fn PdhAddEnglishCounterW(query: PdhHQuery, path: *const u16, userdata: usize, counter: *mut PdhHCounter) -> u32;
         fn PdhCollectQueryData(query: PdhHQuery) -> u32;
-        fn PdhGetFormattedCounterValue(counter: PdhHCounter, fmt: u32, ctype: *mut u32, value: *mut PdhFmtCounterValue) -> u32;
+        fn PdhGetFormattedCounterArrayW(counter: PdhHCounter, fmt: u32, buffer_size: *mut u32, item_count: *mut u32, items: *mut PdhFmtCounterValueItem) -> u32;
         fn PdhCloseQuery(query: PdhHQuery) -> u32;
     }
 
     const PDH_FMT_DOUBLE: u32 = 0x00000200;
+    const PDH_MORE_DATA: u32 = 0x800007D2;
 
     pub struct PdhDisk {
         query: PdhHQuery,
         counter_read: PdhHCounter,
         counter_write: PdhHCounter,
+        counter_idle: PdhHCounter,
+        counter_latency: PdhHCounter,
+        counter_queue: PdhHCounter,
     }
 
     impl PdhDisk {
@@ -80,9 +134,16 @@ mod disk_pdh {
 
             let mut counter_read: PdhHCounter = 0;
             let mut counter_write: PdhHCounter = 0;
+            let mut counter_idle: PdhHCounter = 0;
+            let mut counter_latency: PdhHCounter = 0;
+            let mut counter_queue: PdhHCounter = 0;
 
-            if !add("\\PhysicalDisk(_Total)\\Disk Read Bytes/sec", &mut counter_read)
-                || !add("\\PhysicalDisk(_Total)\\Disk Write Bytes/sec", &mut counter_write)
+            // Wildcard instance: one value per physical disk plus _Total
+            if !add("\\PhysicalDisk(*)\\Disk Read Bytes/sec", &mut counter_read)
+                || !add("\\PhysicalDisk(*)\\Disk Write Bytes/sec", &mut counter_write)
+                || !add("\\PhysicalDisk(*)\\% Idle Time", &mut counter_idle)
+                || !add("\\PhysicalDisk(*)\\Avg. Disk sec/Transfer", &mut counter_latency)
+                || !add("\\PhysicalDisk(*)\\Avg. Disk Queue Length", &mut counter_queue)
             {
                 unsafe { PdhCloseQuery(query) };
                 return None;
@@ -91,30 +152,82 @@ mod disk_pdh {
             // First collection to initialize rate counters
             unsafe { PdhCollectQueryData(query) };
 
-            Some(Self { query, counter_read, counter_write })
+            Some(Self {
+                query,
+                counter_read,
+                counter_write,
+                counter_idle,
+                counter_latency,
+                counter_queue,
+            })
         }
 
-        pub fn collect(&self) -> (f64, f64) {
+        pub fn collect(&self) -> Vec<DiskStats> {
             if unsafe { PdhCollectQueryData(self.query) } != 0 {
-                return (0.0, 0.0);
+                return Vec::new();
             }
-            (self.get_mb(self.counter_read), self.get_mb(self.counter_write))
+
+            let writes: HashMap<String, f64> = self.get_array(self.counter_write).into_iter().collect();
+            let idle: HashMap<String, f64> = self.get_array(self.counter_idle).into_iter().collect();
+            let latency: HashMap<String, f64> = self.get_array(self.counter_latency).into_iter().collect();
+            let queue: HashMap<String, f64> = self.get_array(self.counter_queue).into_iter().collect();
+
+            self.get_array(self.counter_read)
+                .into_iter()
+                .filter(|(name, _)| name != "_Total")
+                .map(|(name, read)| DiskStats {
+                    read_mb: (read / 1_048_576.0).max(0.0),
+                    write_mb: (writes.get(&name).copied().unwrap_or(0.0) / 1_048_576.0).max(0.0),
+                    // "% Idle Time" is reliable, unlike "% Disk Time" which goes past 100
+                    busy_percent: (100.0 - idle.get(&name).copied().unwrap_or(100.0)).clamp(0.0, 100.0) as f32,
+                    // Reported in seconds per transfer
+                    await_ms: (latency.get(&name).copied().unwrap_or(0.0) * 1000.0).max(0.0) as f32,
+                    queue_depth: queue.get(&name).copied().unwrap_or(0.0).max(0.0) as f32,
+                    name,
+                })
+                .collect()
         }
 
-        fn get_mb(&self, counter: PdhHCounter) -> f64 {
-            let mut val: PdhFmtCounterValue = unsafe { zeroed() };
+        /// Reads every instance of a wildcard counter as `(instance name, value)`.
+        fn get_array(&self, counter: PdhHCounter) -> Vec<(String, f64)> {
+            let mut size: u32 = 0;
+            let mut count: u32 = 0;
             let status = unsafe {
-                PdhGetFormattedCounterValue(counter, PDH_FMT_DOUBLE, std::ptr::null_mut(), &mut val)
+                PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut size, &mut count, null_mut())
             };
-            // 0 = PDH_CSTATUS_VALID_DATA, 1 = PDH_CSTATUS_NEW_DATA (both are success)
-            if status == 0 || status == 1 {
-                (unsafe { val.value.double_value } / 1_048_576.0).max(0.0)
-            } else {
-                0.0
+            if status != PDH_MORE_DATA || size == 0 {
+                return Vec::new();
+            }
+
+            // The buffer holds the items followed by their name strings, so it is
+            // sized in bytes; a Vec of items keeps the required alignment.
+            let mut items: Vec<PdhFmtCounterValueItem> =
+                Vec::with_capacity(size as usize / size_of::<PdhFmtCounterValueItem>() + 1);
+            let status = unsafe {
+                PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut size, &mut count, items.as_mut_ptr())
+            };
+            if status != 0 {
+                return Vec::new();
             }
+            unsafe { items.set_len(count as usize) };
+
+            items
+                .iter()
+                // 0 = PDH_CSTATUS_VALID_DATA, 1 = PDH_CSTATUS_NEW_DATA (both are success)
+                .filter(|item| item.value.c_status == 0 || item.value.c_status == 1)
+                .map(|item| (wide_to_string(item.name), unsafe { item.value.value.double_value }))
+                .collect()
         }
     }
 
+    fn wide_to_string(ptr: *const u16) -> String {
+        if ptr.is_null() {
+            return String::new();
+        }
+        let len = (0..).take_while(|&i| unsafe { *ptr.add(i) } != 0).count();
+        String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+
     impl Drop for PdhDisk {
         fn drop(&mut self) {
             unsafe { PdhCloseQuery(self.query) };
@@ -122,103 +235,1278 @@ mod disk_pdh {
     }
 }
 
-pub struct StatsCollector {
-    sys: System,
-    networks: Networks,
-    #[cfg(target_os = "windows")]
-    disk_pdh: Option<disk_pdh::PdhDisk>,
-    gpu: Option<crate::gpu::GpuMonitor>,
+// ---------------------------------------------------------------------------
+// Disk I/O via /proc/diskstats (Linux)
+// The sector counters are cumulative since boot, so rates come from the delta
+// between two reads. The kernel always reports 512-byte sectors in this file,
+// regardless of the device's physical sector size.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+mod disk_proc {
+    use super::DiskStats;
+    use crate::rate::RateCounter;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    const SECTOR_BYTES: f64 = 512.0;
+
+    #[derive(Clone, Copy)]
+    struct DiskCounters {
+        sectors_read: u64,
+        sectors_written: u64,
+        /// Reads + writes completed
+        ios: u64,
+        /// Milliseconds spent on those reads + writes, from queueing to completion
+        io_ms: u64,
+        /// Milliseconds the device had at least one request in flight
+        io_ticks_ms: u64,
+        /// io_ms weighted by the number of requests in flight
+        weighted_ms: u64,
+    }
+
+    #[derive(Default)]
+    struct DiskRates {
+        read: RateCounter,
+        write: RateCounter,
+        ios: RateCounter,
+        io_ms: RateCounter,
+        io_ticks: RateCounter,
+        weighted: RateCounter,
+    }
+
+    pub struct ProcDisk {
+        diskstats: PathBuf,
+        rates: HashMap<String, DiskRates>,
+    }
+
+    impl ProcDisk {
+        pub fn new() -> Self {
+            Self::with_proc_root("/proc")
+        }
+
+        /// Reads `<proc_root>/diskstats` instead of the live file.
+        pub fn with_proc_root(proc_root: impl Into<PathBuf>) -> Self {
+            Self {
+                diskstats: proc_root.into().join("diskstats"),
+                rates: HashMap::new(),
+            }
+        }
+
+        pub fn collect(&mut self, now: Instant) -> Vec<DiskStats> {
+            let counters = match fs::read_to_string(&self.diskstats) {
+                Ok(content) => parse_diskstats(&content),
+                Err(_) => return Vec::new(),
+            };
+
+            // Forget devices that were unplugged
+            self.rates.retain(|name, _| counters.iter().any(|(n, _)| n == name));
+
+            counters
+                .into_iter()
+                .map(|(name, cur)| {
+                    let rates = self.rates.entry(name.clone()).or_default();
+                    // Like iostat: await is time per request over the window and
+                    // queue depth is weighted time per elapsed millisecond
+                    let ios = rates.ios.update(now, cur.ios);
+                    let io_ms = rates.io_ms.update(now, cur.io_ms);
+                    DiskStats {
+                        read_mb: rates.read.update(now, cur.sectors_read) * SECTOR_BYTES / 1_048_576.0,
+                        write_mb: rates.write.update(now, cur.sectors_written) * SECTOR_BYTES / 1_048_576.0,
+                        // Busy milliseconds per second, as a percentage
+                        busy_percent: (rates.io_ticks.update(now, cur.io_ticks_ms) / 10.0).min(100.0) as f32,
+                        await_ms: if ios > 0.0 { (io_ms / ios) as f32 } else { 0.0 },
+                        queue_depth: (rates.weighted.update(now, cur.weighted_ms) / 1000.0) as f32,
+                        name,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    /// Counters for whole physical disks, in file order.
+    /// Line format: `major minor name reads merged sectors_read ms writes merged sectors_written ms in_flight io_ticks weighted_ms ...`
+    fn parse_diskstats(content: &str) -> Vec<(String, DiskCounters)> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 14 || !is_physical_disk(fields[2]) {
+                    return None;
+                }
+                let field = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+                Some((
+                    fields[2].to_string(),
+                    DiskCounters {
+                        sectors_read: field(5),
+                        sectors_written: field(9),
+                        ios: field(3) + field(7),
+                        io_ms: field(6) + field(10),
+                        io_ticks_ms: field(12),
+                        weighted_ms: field(13),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Partitions would double-count their parent disk, and loop/ram/zram or
+    /// device-mapper/md volumes are virtual devices stacked on real ones.
+    fn is_physical_disk(name: &str) -> bool {
+        const VIRTUAL: [&str; 5] = ["loop", "ram", "zram", "dm-", "md"];
+        if VIRTUAL.iter().any(|p| name.starts_with(p)) {
+            return false;
+        }
+
+        let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+        if base.len() == name.len() {
+            // No trailing number: sda, vdb, xvdc
+            return true;
+        }
+        if name.starts_with("nvme") || name.starts_with("mmcblk") {
+            // nvme0n1 / mmcblk0 are disks, nvme0n1p2 / mmcblk0p1 are partitions
+            return !base.ends_with('p');
+        }
+        // sda1, hdb2, vda3, xvda1 are partitions; anything else (sr0...) is a device
+        !["sd", "hd", "vd", "xvd"]
+            .iter()
+            .any(|p| base.starts_with(p) && base[p.len()..].chars().all(|c| c.is_ascii_lowercase()))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::fixtures::FixtureDir;
+        use std::time::Duration;
+
+        // Whole disks sda and nvme0n1, plus partitions and virtual devices to skip
+        const BEFORE: &str = "\
+   8       0 sda 100 0 2048 50 100 0 4096 50 0 100 100
+   8       1 sda1 90 0 2000 40 90 0 4000 40 0 90 90
+ 259       0 nvme0n1 10 0 80 5 10 0 80 5 0 10 10
+ 259       2 nvme0n1p2 10 0 80 5 10 0 80 5 0 10 10
+   7       0 loop0 5 0 40 1 0 0 0 0 0 1 1
+ 253       0 dm-0 50 0 1000 20 50 0 2000 20 0 40 40
+";
+        // Two seconds later: sda read 4096 sectors (2 MiB) and wrote 2048 (1 MiB),
+        // completed 300 I/Os taking 600 ms, was busy 1000 ms, weighted 3000 ms
+        const AFTER: &str = "\
+   8       0 sda 300 0 6144 350 200 0 6144 350 0 1100 3100
+   8       1 sda1 290 0 6000 340 190 0 6000 340 0 1090 3090
+ 259       0 nvme0n1 10 0 80 5 10 0 80 5 0 10 10
+ 259       2 nvme0n1p2 10 0 80 5 10 0 80 5 0 10 10
+   7       0 loop0 500 0 4000 100 0 0 0 0 0 100 100
+ 253       0 dm-0 250 0 5000 320 150 0 6000 320 0 1040 3040
+";
+
+        #[test]
+        fn reports_whole_disks_with_rates() {
+            let proc_root = FixtureDir::new();
+            let mut disks = ProcDisk::with_proc_root(proc_root.path());
+            let t0 = Instant::now();
+
+            proc_root.write("diskstats", BEFORE);
+            let first = disks.collect(t0);
+            let names: Vec<&str> = first.iter().map(|d| d.name.as_str()).collect();
+            assert_eq!(names, ["sda", "nvme0n1"]);
+            assert!(first.iter().all(|d| d.read_mb == 0.0 && d.write_mb == 0.0));
+
+            proc_root.write("diskstats", AFTER);
+            let second = disks.collect(t0 + Duration::from_secs(2));
+            let sda = &second[0];
+            assert_eq!(sda.read_mb, 1.0);
+            assert_eq!(sda.write_mb, 0.5);
+            assert_eq!(sda.busy_percent, 50.0);
+            // 300 ms/s of I/O time over 150 I/Os/s
+            assert_eq!(sda.await_ms, 2.0);
+            assert_eq!(sda.queue_depth, 1.5);
+
+            let nvme = &second[1];
+            assert_eq!((nvme.read_mb, nvme.busy_percent, nvme.await_ms), (0.0, 0.0, 0.0));
+        }
+
+        #[test]
+        fn missing_file_yields_no_disks() {
+            let proc_root = FixtureDir::new();
+            assert!(ProcDisk::with_proc_root(proc_root.path()).collect(Instant::now()).is_empty());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Filesystem space and inodes for one mount point or drive letter
+// Used % follows df: used / (used + available), so the ext4 root reserve
+// doesn't make a full disk read as 95%.
+// ---------------------------------------------------------------------------
+#[cfg(unix)]
+fn filesystem_usage(mount: &str) -> Option<FilesystemStats> {
+    let path = std::ffi::CString::new(mount).ok()?;
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut st) } != 0 {
+        return None;
+    }
+
+    let block = st.f_frsize as u64;
+    let total = st.f_blocks as u64 * block;
+    let used = (st.f_blocks as u64).saturating_sub(st.f_bfree as u64) * block;
+    let available = st.f_bavail as u64 * block;
+
+    let files = st.f_files as u64;
+    let inodes_used_percent = (files > 0).then(|| {
+        let used = files.saturating_sub(st.f_ffree as u64);
+        (used as f64 / files as f64 * 100.0) as f32
+    });
+
+    Some(filesystem_stats(mount, used, available, total, inodes_used_percent))
+}
+
+#[cfg(target_os = "windows")]
+fn filesystem_usage(mount: &str) -> Option<FilesystemStats> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    // A bare `C:` would mean the current directory on that drive
+    let mut root = mount.to_string();
+    if !root.ends_with('\\') {
+        root.push('\\');
+    }
+    let wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+
+    let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+    if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) } == 0 {
+        return None;
+    }
+
+    Some(filesystem_stats(mount, total.saturating_sub(free), available, total, None))
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn filesystem_usage(_mount: &str) -> Option<FilesystemStats> {
+    None
+}
+
+fn filesystem_stats(mount: &str, used: u64, available: u64, total: u64, inodes_used_percent: Option<f32>) -> FilesystemStats {
+    let usable = used + available;
+    FilesystemStats {
+        mount: mount.to_string(),
+        used_gb: used as f64 / 1_073_741_824.0,
+        free_gb: available as f64 / 1_073_741_824.0,
+        total_gb: total as f64 / 1_073_741_824.0,
+        used_percent: if usable > 0 {
+            (used as f64 / usable as f64 * 100.0) as f32
+        } else {
+            0.0
+        },
+        inodes_used_percent,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Negotiated link speed per interface, in Mb/s
+// Virtual interfaces and links that are down report no speed and are left out.
+// ---------------------------------------------------------------------------
+#[cfg(target_os = "linux")]
+fn link_speeds_mbps() -> HashMap<String, u64> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            // Reading `speed` fails with EINVAL when the link is down, and is -1 for some drivers
+            let speed = std::fs::read_to_string(entry.path().join("speed")).ok()?;
+            let mbps = speed.trim().parse::<i64>().ok().filter(|&s| s > 0)?;
+            Some((entry.file_name().to_string_lossy().into_owned(), mbps as u64))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn link_speeds_mbps() -> HashMap<String, u64> {
+    use windows_sys::Win32::NetworkManagement::IpHelper::{FreeMibTable, GetIfTable2, MIB_IF_TABLE2};
+
+    let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+    if unsafe { GetIfTable2(&mut table) } != 0 {
+        return HashMap::new();
+    }
+
+    // Keyed by Alias, the same name sysinfo uses for the interface
+    let rows = unsafe { std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize) };
+    let speeds = rows
+        .iter()
+        .filter_map(|row| {
+            let bps = row.ReceiveLinkSpeed.max(row.TransmitLinkSpeed);
+            if bps == 0 || bps == u64::MAX {
+                return None;
+            }
+            let len = row.Alias.iter().position(|&c| c == 0).unwrap_or(row.Alias.len());
+            Some((String::from_utf16_lossy(&row.Alias[..len]), bps / 1_000_000))
+        })
+        .collect();
+
+    unsafe { FreeMibTable(table as *const _) };
+    speeds
 }
 
-impl StatsCollector {
-    pub fn new() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-        let networks = Networks::new_with_refreshed_list();
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn link_speeds_mbps() -> HashMap<String, u64> {
+    HashMap::new()
+}
+
+// ---------------------------------------------------------------------------
+// Built-in metric sources
+// Each one emits the metrics in its `*_METRICS` list and skips the reads that
+// no shown metric needs. Sources share nothing but the merged `SystemStats`.
+// ---------------------------------------------------------------------------
+
+/// A registry holding every built-in source.
+pub fn default_registry() -> MetricRegistry {
+    let mut registry = MetricRegistry::new();
+    registry.register(Box::new(CpuSource::new()));
+    registry.register(Box::new(CpuTimesSource(CpuTimeMonitor::new())));
+    registry.register(Box::new(MemorySource::new()));
+    registry.register(Box::new(GpuSource { monitor: None }));
+    registry.register(Box::new(PowerSource::new()));
+    registry.register(Box::new(DiskSource::new()));
+    registry.register(Box::new(FilesystemSource));
+    registry.register(Box::new(NetworkSource::new()));
+    registry.register(Box::new(LatencySource(LatencyProbe::new())));
+    registry.register(Box::new(SensorSource(SensorMonitor::new())));
+    registry.register(Box::new(LoadSource));
+    registry.register(Box::new(PressureSource(PressureMonitor::new())));
+    registry.register(Box::new(KernelActivitySource(KernelActivityMonitor::new())));
+    registry.register(Box::new(TopProcessSource::new()));
+    registry.register(Box::new(TrackedProcessSource::new()));
+    registry.register(Box::new(BatterySource(BatteryMonitor::new())));
+    registry
+}
 
+/// A `System` that knows the CPU count (needed to turn process CPU time into
+/// a share of the machine) and nothing else until refreshed.
+fn system_with_cpu_list() -> System {
+    System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing()))
+}
+
+// --- CPU usage and clocks --------------------------------------------------
+
+const CPU_METRICS: &[MetricDef] = &[
+    MetricDef::new("cpu_percent", Unit::Percent),
+    MetricDef::new("cpu_freq_ghz", Unit::Ghz),
+    // Busiest single core, so one pegged thread is visible on many-core CPUs
+    MetricDef::new("cpu_max_core_percent", Unit::Percent),
+    MetricDef::new("cpu_max_freq_ghz", Unit::Ghz),
+    // Average clock as a share of the highest boost clock
+    MetricDef::new("cpu_freq_percent_of_max", Unit::Percent),
+    MetricDef::new("cpu_governor", Unit::None),
+    // Recent thermal_throttle events (Linux) or a clock cap below max (Windows)
+    MetricDef::new("cpu_throttling", Unit::None),
+    // Per logical core, in OS order (same index in both series)
+    MetricDef::new("cpu_core_percent", Unit::Percent),
+    MetricDef::new("cpu_core_freq_mhz", Unit::Mhz),
+    // Hybrid CPUs only: P-core and E-core averages (missing elsewhere)
+    MetricDef::new("pcore_percent", Unit::Percent),
+    MetricDef::new("pcore_freq_ghz", Unit::Ghz),
+    MetricDef::new("ecore_percent", Unit::Percent),
+    MetricDef::new("ecore_freq_ghz", Unit::Ghz),
+];
+
+const CPU_USAGE: &[&str] = &[
+    "cpu_percent",
+    "cpu_max_core_percent",
+    "cpu_core_percent",
+    "pcore_percent",
+    "ecore_percent",
+];
+const CPU_CLOCKS: &[&str] = &[
+    "cpu_freq_ghz",
+    "cpu_max_freq_ghz",
+    "cpu_freq_percent_of_max",
+    "cpu_core_freq_mhz",
+    "pcore_freq_ghz",
+    "ecore_freq_ghz",
+];
+const CPU_LIMITS: &[&str] = &["cpu_freq_percent_of_max", "cpu_governor", "cpu_throttling"];
+
+struct CpuSource {
+    sys: System,
+    cpufreq: CpuFreqMonitor,
+    core_types: Option<CoreTypes>,
+}
+
+impl CpuSource {
+    fn new() -> Self {
         Self {
-            sys,
-            networks,
-            #[cfg(target_os = "windows")]
-            disk_pdh: disk_pdh::PdhDisk::new(),
-            gpu: crate::gpu::GpuMonitor::new().ok(),
+            sys: System::new(),
+            cpufreq: CpuFreqMonitor::new(),
+            core_types: CoreTypes::detect(),
         }
     }
+}
 
-    pub fn collect(&mut self) -> SystemStats {
-        self.sys.refresh_cpu_all();
-        self.sys.refresh_memory();
-        self.networks.refresh(false);
+impl MetricSource for CpuSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Cpu
+    }
 
-        let cpu_percent = self.sys.global_cpu_usage();
+    fn metrics(&self) -> &'static [MetricDef] {
+        CPU_METRICS
+    }
 
-        let cpu_freq_ghz = {
-            let cpus = self.sys.cpus();
-            if cpus.is_empty() {
-                0.0_f32
-            } else {
-                let avg_mhz =
-                    cpus.iter().map(|c| c.frequency() as f64).sum::<f64>() / cpus.len() as f64;
-                (avg_mhz / 1000.0) as f32
+    fn reset(&mut self) {
+        // sysinfo usage and throttle events are deltas since the previous refresh
+        *self = Self::new();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        let usage = ctx.wants_any(CPU_USAGE);
+        let clocks = ctx.wants_any(CPU_CLOCKS);
+        if usage || clocks {
+            let mut kind = CpuRefreshKind::nothing();
+            if usage {
+                kind = kind.with_cpu_usage();
+            }
+            if clocks {
+                kind = kind.with_frequency();
             }
+            self.sys.refresh_cpu_specifics(kind);
+        }
+
+        let cpu_core_percent: Vec<f32> = self.sys.cpus().iter().map(|c| c.cpu_usage()).collect();
+        let cpu_core_freq_mhz: Vec<u64> = self.sys.cpus().iter().map(|c| c.frequency()).collect();
+
+        let cpu_freq_ghz = if cpu_core_freq_mhz.is_empty() {
+            0.0_f32
+        } else {
+            let avg_mhz = cpu_core_freq_mhz.iter().sum::<u64>() as f64 / cpu_core_freq_mhz.len() as f64;
+            (avg_mhz / 1000.0) as f32
+        };
+
+        // P-core / E-core split, so efficiency cores don't dilute the average
+        let (pcore, ecore) = match &self.core_types {
+            Some(types) => (
+                group_average(&types.performance, &cpu_core_percent, &cpu_core_freq_mhz),
+                group_average(&types.efficiency, &cpu_core_percent, &cpu_core_freq_mhz),
+            ),
+            None => (None, None),
         };
 
+        out.set("cpu_percent", self.sys.global_cpu_usage());
+        out.set("cpu_freq_ghz", cpu_freq_ghz);
+        out.set("cpu_max_core_percent", cpu_core_percent.iter().copied().fold(0.0_f32, f32::max));
+        out.set("cpu_max_freq_ghz", cpu_core_freq_mhz.iter().copied().max().unwrap_or(0) as f32 / 1000.0);
+        out.set("pcore_percent", pcore.map(|(usage, _)| usage));
+        out.set("pcore_freq_ghz", pcore.map(|(_, ghz)| ghz));
+        out.set("ecore_percent", ecore.map(|(usage, _)| usage));
+        out.set("ecore_freq_ghz", ecore.map(|(_, ghz)| ghz));
+        out.set("cpu_core_percent", cpu_core_percent);
+        out.set("cpu_core_freq_mhz", cpu_core_freq_mhz);
+
+        // Headroom to the boost clock, governor and throttle events
+        if ctx.wants_any(CPU_LIMITS) {
+            let freq = self.cpufreq.refresh(ctx.now);
+            out.set(
+                "cpu_freq_percent_of_max",
+                freq.max_mhz
+                    .filter(|_| cpu_freq_ghz > 0.0)
+                    .map(|max| (cpu_freq_ghz * 1000.0 / max as f32 * 100.0).min(100.0)),
+            );
+            out.set("cpu_governor", freq.governor);
+            out.set("cpu_throttling", freq.throttling);
+        }
+    }
+}
+
+// --- CPU time breakdown ----------------------------------------------------
+
+/// Percentages of all CPU time (Linux only)
+const CPU_TIMES_METRICS: &[MetricDef] = &[
+    MetricDef::new("cpu_user_percent", Unit::Percent),
+    MetricDef::new("cpu_nice_percent", Unit::Percent),
+    MetricDef::new("cpu_system_percent", Unit::Percent),
+    MetricDef::new("cpu_iowait_percent", Unit::Percent),
+    MetricDef::new("cpu_irq_percent", Unit::Percent),
+    MetricDef::new("cpu_softirq_percent", Unit::Percent),
+    MetricDef::new("cpu_steal_percent", Unit::Percent),
+];
+
+struct CpuTimesSource(CpuTimeMonitor);
+
+impl MetricSource for CpuTimesSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Cpu
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        CPU_TIMES_METRICS
+    }
+
+    fn reset(&mut self) {
+        self.0 = CpuTimeMonitor::new();
+    }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        // user/system/iowait/steal... split from /proc/stat jiffies
+        let times = self.0.refresh();
+        out.set("cpu_user_percent", times.as_ref().map(|t| t.user));
+        out.set("cpu_nice_percent", times.as_ref().map(|t| t.nice));
+        out.set("cpu_system_percent", times.as_ref().map(|t| t.system));
+        out.set("cpu_iowait_percent", times.as_ref().map(|t| t.iowait));
+        out.set("cpu_irq_percent", times.as_ref().map(|t| t.irq));
+        out.set("cpu_softirq_percent", times.as_ref().map(|t| t.softirq));
+        out.set("cpu_steal_percent", times.as_ref().map(|t| t.steal));
+    }
+}
+
+// --- Memory ----------------------------------------------------------------
+
+const MEMORY_METRICS: &[MetricDef] = &[
+    MetricDef::new("ram_percent", Unit::Percent),
+    MetricDef::new("ram_used_gb", Unit::Gigabytes),
+    MetricDef::new("ram_total_gb", Unit::Gigabytes),
+    MetricDef::new("ram_available_gb", Unit::Gigabytes),
+    MetricDef::new("ram_cached_gb", Unit::Gigabytes),
+    MetricDef::new("commit_gb", Unit::Gigabytes),
+    MetricDef::new("commit_limit_gb", Unit::Gigabytes),
+    MetricDef::new("swap_percent", Unit::Percent),
+    MetricDef::new("swap_used_gb", Unit::Gigabytes),
+    MetricDef::new("swap_total_gb", Unit::Gigabytes),
+];
+
+const RAM: &[&str] = &["ram_percent", "ram_used_gb", "ram_total_gb", "ram_available_gb"];
+const SWAP: &[&str] = &["swap_percent", "swap_used_gb", "swap_total_gb"];
+const MEMORY_DETAILS: &[&str] = &["ram_available_gb", "ram_cached_gb", "commit_gb", "commit_limit_gb"];
+
+struct MemorySource {
+    sys: System,
+    memory: MemoryMonitor,
+}
+
+impl MemorySource {
+    fn new() -> Self {
+        Self {
+            sys: System::new(),
+            memory: MemoryMonitor::new(),
+        }
+    }
+}
+
+impl MetricSource for MemorySource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Memory
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        MEMORY_METRICS
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        let mut kind = MemoryRefreshKind::nothing();
+        if ctx.wants_any(RAM) {
+            kind = kind.with_ram();
+        }
+        if ctx.wants_any(SWAP) {
+            kind = kind.with_swap();
+        }
+        self.sys.refresh_memory_specifics(kind);
+
+        let to_gb = |bytes: u64| (bytes as f64 / 1_073_741_824.0) as f32;
+
         let ram_total = self.sys.total_memory() as f64;
         let ram_used = self.sys.used_memory() as f64;
-        let ram_percent = if ram_total > 0.0 {
-            (ram_used / ram_total * 100.0) as f32
+        out.set(
+            "ram_percent",
+            if ram_total > 0.0 {
+                (ram_used / ram_total * 100.0) as f32
+            } else {
+                0.0
+            },
+        );
+        out.set("ram_used_gb", to_gb(self.sys.used_memory()));
+        out.set("ram_total_gb", to_gb(self.sys.total_memory()));
+
+        // Page cache and commit charge from /proc/meminfo or GetPerformanceInfo
+        let mem = if ctx.wants_any(MEMORY_DETAILS) {
+            self.memory.refresh()
         } else {
-            0.0
+            MemoryDetails::default()
         };
-        let ram_used_gb = (ram_used / 1_073_741_824.0) as f32;
-        let ram_total_gb = (ram_total / 1_073_741_824.0) as f32;
+        out.set("ram_available_gb", to_gb(mem.available.unwrap_or_else(|| self.sys.available_memory())));
+        out.set("ram_cached_gb", mem.cached.map(to_gb));
+        out.set("commit_gb", mem.committed.map(to_gb));
+        out.set("commit_limit_gb", mem.commit_limit.map(to_gb));
+
+        // Swap (pagefile on Windows)
+        let swap_total = self.sys.total_swap();
+        let swap_used = self.sys.used_swap();
+        out.set(
+            "swap_percent",
+            if swap_total > 0 {
+                (swap_used as f64 / swap_total as f64 * 100.0) as f32
+            } else {
+                0.0
+            },
+        );
+        out.set("swap_used_gb", to_gb(swap_used));
+        out.set("swap_total_gb", to_gb(swap_total));
+    }
+}
+
+// --- NVIDIA GPU ------------------------------------------------------------
+
+const GPU_METRICS: &[MetricDef] = &[
+    MetricDef::new("gpu_percent", Unit::Percent),
+    MetricDef::new("gpu_temp", Unit::Celsius),
+    MetricDef::new("gpu_power_w", Unit::Watts),
+    MetricDef::new("gpu_clock_mhz", Unit::Mhz),
+    MetricDef::new("vram_used_mb", Unit::Megabytes),
+    MetricDef::new("vram_total_mb", Unit::Megabytes),
+    // NVML clock limiters currently active (power cap, thermal...)
+    MetricDef::new("gpu_throttle_reasons", Unit::None),
+];
+
+struct GpuSource {
+    /// NVML is loaded on the first refresh (outer `None` until then), so a
+    /// user who shows no GPU metric never loads the driver library
+    monitor: Option<Option<crate::gpu::GpuMonitor>>,
+}
+
+impl MetricSource for GpuSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Gpu
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        GPU_METRICS
+    }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        let gpu = match self.monitor.get_or_insert_with(|| crate::gpu::GpuMonitor::new().ok()) {
+            Some(gpu) => gpu.refresh(),
+            None => crate::gpu::GpuStats::default(),
+        };
+        out.set("gpu_percent", gpu.percent);
+        out.set("gpu_temp", gpu.temp);
+        out.set("gpu_power_w", gpu.power_w);
+        out.set("gpu_clock_mhz", gpu.clock_mhz);
+        out.set("vram_used_mb", gpu.vram_used_mb);
+        out.set("vram_total_mb", gpu.vram_total_mb);
+        out.set("gpu_throttle_reasons", MetricValue::detail(&gpu.throttle_reasons));
+    }
+}
 
-        // Disk I/O via PDH — returns MB/s directly (PDH computes the rate)
+// --- CPU power and session energy ------------------------------------------
+
+const POWER_METRICS: &[MetricDef] = &[
+    // RAPL package and DRAM power (Linux)
+    MetricDef::new("cpu_package_w", Unit::Watts),
+    MetricDef::new("dram_w", Unit::Watts),
+    // CPU package + DRAM + GPU energy counted while shown; missing without RAPL
+    // (Windows, or `energy_uj` readable only by root), as GPU alone would under-report
+    MetricDef::new("session_energy_wh", Unit::WattHours),
+    // `session_energy_wh` at the configured electricity price
+    MetricDef::new("session_cost", Unit::None),
+];
+
+struct PowerSource {
+    rapl: RaplMonitor,
+    energy: EnergyMeter,
+}
+
+impl PowerSource {
+    fn new() -> Self {
+        Self {
+            rapl: RaplMonitor::new(),
+            energy: EnergyMeter::default(),
+        }
+    }
+}
+
+impl MetricSource for PowerSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Power
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        POWER_METRICS
+    }
+
+    fn reset(&mut self) {
+        self.rapl = RaplMonitor::new();
+        self.energy.pause();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // CPU power from RAPL counters; the session energy adds up every
+        // source we can measure (CPU package, DRAM, and the GPU's latest reading)
+        let cpu_power = self.rapl.refresh(ctx.now);
+        let gpu_w = out.number("gpu_power_w").map(|w| w as f32);
+        let measured_w = [cpu_power.package_w, cpu_power.dram_w, gpu_w]
+            .into_iter()
+            .flatten()
+            .sum::<f32>();
+        // Only counted while shown, so CPU-only readings don't stand in for the total
+        let session_energy_wh = match cpu_power.package_w {
+            None => {
+                self.energy.pause();
+                None
+            }
+            Some(_) if ctx.wants("session_energy_wh") => Some(self.energy.update(ctx.now, measured_w as f64)),
+            Some(_) => {
+                self.energy.pause();
+                Some(self.energy.wh())
+            }
+        };
+
+        out.set("cpu_package_w", cpu_power.package_w);
+        out.set("dram_w", cpu_power.dram_w);
+        out.set("session_energy_wh", session_energy_wh);
+        out.set(
+            "session_cost",
+            session_energy_wh
+                .zip(ctx.cfg.power.price_per_kwh)
+                .map(|(wh, price)| wh / 1000.0 * price),
+        );
+    }
+}
+
+// --- Disk I/O --------------------------------------------------------------
+
+const DISK_METRICS: &[MetricDef] = &[
+    MetricDef::new("disk_read_mb", Unit::MegabytesPerSec),
+    MetricDef::new("disk_write_mb", Unit::MegabytesPerSec),
+    MetricDef::new("disks", Unit::None),
+];
+
+struct DiskSource {
+    /// Opened on the first refresh (outer `None` until then), like NVML
+    #[cfg(target_os = "windows")]
+    pdh: Option<Option<disk_pdh::PdhDisk>>,
+    #[cfg(target_os = "linux")]
+    proc: disk_proc::ProcDisk,
+}
+
+impl DiskSource {
+    fn new() -> Self {
+        Self {
+            #[cfg(target_os = "windows")]
+            pdh: None,
+            #[cfg(target_os = "linux")]
+            proc: disk_proc::ProcDisk::new(),
+        }
+    }
+}
+
+impl MetricSource for DiskSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Disk
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        DISK_METRICS
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // Disk I/O via PDH — returns MB/s directly (PDH computes the rate from
+        // its own timestamps between two PdhCollectQueryData calls)
         #[cfg(target_os = "windows")]
-        let (disk_read_mb, disk_write_mb) = self
-            .disk_pdh
+        let disks = self
+            .pdh
+            .get_or_insert_with(disk_pdh::PdhDisk::new)
             .as_ref()
             .map(|d| d.collect())
-            .unwrap_or((0.0, 0.0));
+            .unwrap_or_default();
+
+        // Disk I/O via /proc/diskstats — rate from sector deltas between calls
+        #[cfg(target_os = "linux")]
+        let disks = self.proc.collect(ctx.now);
+
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        let disks: Vec<DiskStats> = Vec::new();
+
+        out.set("disk_read_mb", disks.iter().map(|d| d.read_mb).sum::<f64>());
+        out.set("disk_write_mb", disks.iter().map(|d| d.write_mb).sum::<f64>());
+        out.set("disks", MetricValue::detail(&disks));
+    }
+}
+
+/// Names of the disks the `disks` metric reports, read directly so the
+/// settings can list them while no disk metric is shown.
+pub fn disk_devices() -> Vec<String> {
+    #[cfg(target_os = "windows")]
+    let disks = disk_pdh::PdhDisk::new().map(|d| d.collect()).unwrap_or_default();
 
-        #[cfg(not(target_os = "windows"))]
-        let (disk_read_mb, disk_write_mb) = (0.0, 0.0);
+    #[cfg(target_os = "linux")]
+    let disks = disk_proc::ProcDisk::new().collect(Instant::now());
 
-        // Network I/O — sysinfo returns delta since the last refresh()
-        let mut net_rx: u64 = 0;
-        let mut net_tx: u64 = 0;
-        for (_, data) in &self.networks {
-            net_rx += data.received();
-            net_tx += data.transmitted();
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    let disks: Vec<DiskStats> = Vec::new();
+
+    disks.into_iter().map(|d| d.name).collect()
+}
+
+// --- Filesystem space ------------------------------------------------------
+
+/// Space on the shown mounts of `filesystems.mounts`
+const FILESYSTEM_METRICS: &[MetricDef] = &[MetricDef::new("filesystems", Unit::None)];
+
+struct FilesystemSource;
+
+impl MetricSource for FilesystemSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Filesystems
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        FILESYSTEM_METRICS
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // Capacity of the shown mounts only, so a hung network mount
+        // elsewhere can't stall the overlay
+        let filesystems: Vec<FilesystemStats> = ctx
+            .cfg
+            .filesystems
+            .mounts
+            .iter()
+            .filter(|m| ctx.cfg.metrics.shown(&filesystem_metric_key(m)))
+            .filter_map(|m| filesystem_usage(m))
+            .collect();
+        out.set("filesystems", MetricValue::detail(&filesystems));
+    }
+}
+
+// --- Network ---------------------------------------------------------------
+
+const NETWORK_METRICS: &[MetricDef] = &[
+    MetricDef::new("net_down_mb", Unit::MegabytesPerSec),
+    MetricDef::new("net_up_mb", Unit::MegabytesPerSec),
+    // Highest link utilization among counted interfaces (only with `show_utilization`)
+    MetricDef::new("net_utilization_percent", Unit::Percent),
+    MetricDef::new("interfaces", Unit::None),
+    // Errors and drops per second, summed over both directions of counted interfaces
+    MetricDef::new("net_errors_per_sec", Unit::PerSec),
+    MetricDef::new("net_drops_per_sec", Unit::PerSec),
+    MetricDef::new("tcp_retrans_per_sec", Unit::PerSec),
+    MetricDef::new("tcp_retrans_percent", Unit::Percent),
+    MetricDef::new("tcp_established", Unit::None),
+];
+
+const NET_TRAFFIC: &[&str] = &[
+    "net_down_mb",
+    "net_up_mb",
+    "net_utilization_percent",
+    "interfaces",
+    "net_errors_per_sec",
+    "net_drops_per_sec",
+];
+const NET_QUALITY: &[&str] = &[
+    "net_errors_per_sec",
+    "net_drops_per_sec",
+    "tcp_retrans_per_sec",
+    "tcp_retrans_percent",
+    "tcp_established",
+];
+
+struct NetworkSource {
+    networks: Networks,
+    /// Per-interface (received, transmitted) byte rates
+    rates: HashMap<String, (RateCounter, RateCounter)>,
+    quality: NetQualityMonitor,
+}
+
+impl NetworkSource {
+    fn new() -> Self {
+        Self {
+            networks: Networks::new(),
+            rates: HashMap::new(),
+            quality: NetQualityMonitor::new(),
         }
-        let net_down_mb = net_rx as f64 / 1_048_576.0;
-        let net_up_mb = net_tx as f64 / 1_048_576.0;
+    }
+
+    /// Network I/O — rates from sysinfo's cumulative totals over real elapsed
+    /// time, since received()/transmitted() are only "since the last refresh()".
+    /// Only interfaces allowed by the filters count, so traffic relayed through
+    /// loopback, bridges or tunnels is not added on top of the physical link.
+    fn interfaces(&mut self, now: Instant, cfg: &AppConfig, quality: &NetQuality) -> Vec<NetInterfaceStats> {
+        self.networks.refresh(false);
+        let link_speeds = if cfg.network.show_utilization {
+            link_speeds_mbps()
+        } else {
+            HashMap::new()
+        };
+        let mut interfaces: Vec<NetInterfaceStats> = self
+            .networks
+            .iter()
+            .filter(|(name, _)| cfg.network.allows(name))
+            .map(|(name, data)| {
+                let (rx, tx) = self.rates.entry(name.clone()).or_default();
+                let down_mb = rx.update(now, data.total_received()) / 1_048_576.0;
+                let up_mb = tx.update(now, data.total_transmitted()) / 1_048_576.0;
+                let link_mbps = link_speeds.get(name).copied();
+                // Link speed is in megabits (10^6), rates are in MiB
+                let utilization_percent = link_mbps.map(|mbps| {
+                    let link_bytes = mbps as f64 * 1_000_000.0 / 8.0;
+                    (down_mb.max(up_mb) * 1_048_576.0 / link_bytes * 100.0).min(100.0) as f32
+                });
+                let q = quality.interfaces.get(name).cloned().unwrap_or_default();
+                NetInterfaceStats {
+                    name: name.clone(),
+                    down_mb,
+                    up_mb,
+                    link_mbps,
+                    utilization_percent,
+                    rx_errors_per_sec: q.rx_errors,
+                    tx_errors_per_sec: q.tx_errors,
+                    rx_drops_per_sec: q.rx_drops,
+                    tx_drops_per_sec: q.tx_drops,
+                }
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        interfaces
+    }
+}
+
+impl MetricSource for NetworkSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Network
+    }
 
-        // GPU stats via NVML
-        let gpu = if let Some(ref mut gpu) = self.gpu {
-            gpu.refresh()
+    fn metrics(&self) -> &'static [MetricDef] {
+        NETWORK_METRICS
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // Retransmits, errors and drops from the kernel's cumulative counters
+        // Counters of a skipped half are dropped, so its rates restart when shown again
+        let quality = if ctx.wants_any(NET_QUALITY) {
+            self.quality.refresh(ctx.now)
+        } else {
+            self.quality = NetQualityMonitor::new();
+            NetQuality::default()
+        };
+        // Per-interface errors are summed over the same filtered interfaces as traffic
+        let interfaces = if ctx.wants_any(NET_TRAFFIC) {
+            self.interfaces(ctx.now, ctx.cfg, &quality)
         } else {
-            crate::gpu::GpuStats::default()
+            self.rates.clear();
+            Vec::new()
         };
 
-        SystemStats {
-            cpu_percent,
-            cpu_freq_ghz,
-            ram_percent,
-            ram_used_gb,
-            ram_total_gb,
-            gpu_percent: gpu.percent,
-            gpu_temp: gpu.temp,
-            gpu_power_w: gpu.power_w,
-            gpu_clock_mhz: gpu.clock_mhz,
-            vram_used_mb: gpu.vram_used_mb,
-            vram_total_mb: gpu.vram_total_mb,
-            disk_read_mb,
-            disk_write_mb,
-            net_down_mb,
-            net_up_mb,
+        out.set("net_down_mb", interfaces.iter().map(|i| i.down_mb).sum::<f64>());
+        out.set("net_up_mb", interfaces.iter().map(|i| i.up_mb).sum::<f64>());
+        out.set(
+            "net_utilization_percent",
+            interfaces.iter().filter_map(|i| i.utilization_percent).reduce(f32::max),
+        );
+        out.set(
+            "net_errors_per_sec",
+            interfaces.iter().map(|i| i.rx_errors_per_sec + i.tx_errors_per_sec).sum::<f32>(),
+        );
+        out.set(
+            "net_drops_per_sec",
+            interfaces.iter().map(|i| i.rx_drops_per_sec + i.tx_drops_per_sec).sum::<f32>(),
+        );
+        out.set("interfaces", MetricValue::detail(&interfaces));
+        out.set("tcp_retrans_per_sec", quality.tcp_retrans_per_sec);
+        out.set("tcp_retrans_percent", quality.tcp_retrans_percent);
+        out.set("tcp_established", quality.tcp_established);
+    }
+}
+
+// --- Latency ---------------------------------------------------------------
+
+/// TCP connect probes, one entry per configured target that has been probed
+const LATENCY_METRICS: &[MetricDef] = &[MetricDef::new("latency", Unit::None)];
+
+struct LatencySource(LatencyProbe);
+
+impl MetricSource for LatencySource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Network
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        LATENCY_METRICS
+    }
+
+    /// The probe only gets targets while a latency metric is shown, so its
+    /// thread idles otherwise.
+    fn configure(&mut self, ctx: &SourceContext) {
+        if ctx.wants("latency") {
+            self.0.configure(&ctx.cfg.latency);
+        } else {
+            self.0.configure(&LatencyConfig {
+                targets: Vec::new(),
+                ..ctx.cfg.latency.clone()
+            });
         }
     }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        // Probed on its own thread; this only reads the latest figures
+        out.set("latency", MetricValue::detail(&self.0.snapshot()));
+    }
+}
+
+// --- Temperatures and fans -------------------------------------------------
+
+const SENSOR_METRICS: &[MetricDef] = &[
+    MetricDef::new("cpu_temp", Unit::Celsius),
+    MetricDef::new("cpu_core_temps", Unit::Celsius),
+    MetricDef::new("nvme_temp", Unit::Celsius),
+    MetricDef::new("chipset_temp", Unit::Celsius),
+    // Fastest spinning fan
+    MetricDef::new("fan_rpm", Unit::Rpm),
+    MetricDef::new("fans", Unit::None),
+];
+
+struct SensorSource(SensorMonitor);
+
+impl MetricSource for SensorSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Sensors
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        SENSOR_METRICS
+    }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        // Temperatures and fans via hwmon (Linux) or sysinfo Components
+        let sensors = self.0.refresh();
+        out.set("cpu_temp", sensors.cpu_temp);
+        out.set("cpu_core_temps", sensors.cpu_core_temps);
+        out.set("nvme_temp", sensors.nvme_temp);
+        out.set("chipset_temp", sensors.chipset_temp);
+        out.set("fan_rpm", sensors.fans.iter().map(|f| f.rpm).max());
+        out.set("fans", MetricValue::detail(&sensors.fans));
+    }
+}
+
+// --- Load, pressure and kernel activity ------------------------------------
+
+const LOAD_METRICS: &[MetricDef] = &[
+    MetricDef::new("load_avg_1", Unit::None),
+    MetricDef::new("load_avg_5", Unit::None),
+    MetricDef::new("load_avg_15", Unit::None),
+];
+
+/// Load average (Unix; sysinfo doesn't support it on Windows and returns zeros)
+struct LoadSource;
+
+impl MetricSource for LoadSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::System
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        LOAD_METRICS
+    }
+
+    #[cfg(not(windows))]
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        let load = System::load_average();
+        out.set("load_avg_1", load.one);
+        out.set("load_avg_5", load.five);
+        out.set("load_avg_15", load.fifteen);
+    }
+
+    #[cfg(windows)]
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        for id in ["load_avg_1", "load_avg_5", "load_avg_15"] {
+            out.set(id, MetricValue::Missing);
+        }
+    }
+}
+
+/// Pressure stall avg10 percentages (Linux only)
+const PRESSURE_METRICS: &[MetricDef] = &[
+    MetricDef::new("psi_cpu_some", Unit::Percent),
+    MetricDef::new("psi_cpu_full", Unit::Percent),
+    MetricDef::new("psi_memory_some", Unit::Percent),
+    MetricDef::new("psi_memory_full", Unit::Percent),
+    MetricDef::new("psi_io_some", Unit::Percent),
+    MetricDef::new("psi_io_full", Unit::Percent),
+];
+
+struct PressureSource(PressureMonitor);
+
+impl MetricSource for PressureSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::System
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        PRESSURE_METRICS
+    }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        let psi = self.0.refresh();
+        out.set("psi_cpu_some", psi.cpu.as_ref().map(|p| p.some_avg10));
+        out.set("psi_cpu_full", psi.cpu.as_ref().and_then(|p| p.full_avg10));
+        out.set("psi_memory_some", psi.memory.as_ref().map(|p| p.some_avg10));
+        out.set("psi_memory_full", psi.memory.as_ref().and_then(|p| p.full_avg10));
+        out.set("psi_io_some", psi.io.as_ref().map(|p| p.some_avg10));
+        out.set("psi_io_full", psi.io.as_ref().and_then(|p| p.full_avg10));
+    }
 }
 
-pub struct StatsState(pub Mutex<StatsCollector>);
+/// Kernel event rates (Linux only)
+const KERNEL_ACTIVITY_METRICS: &[MetricDef] = &[
+    MetricDef::new("context_switches_per_sec", Unit::PerSec),
+    MetricDef::new("interrupts_per_sec", Unit::PerSec),
+    MetricDef::new("forks_per_sec", Unit::PerSec),
+    MetricDef::new("minor_faults_per_sec", Unit::PerSec),
+    MetricDef::new("major_faults_per_sec", Unit::PerSec),
+];
+
+struct KernelActivitySource(KernelActivityMonitor);
+
+impl MetricSource for KernelActivitySource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::System
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        KERNEL_ACTIVITY_METRICS
+    }
+
+    fn reset(&mut self) {
+        self.0 = KernelActivityMonitor::new();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        let activity = self.0.refresh(ctx.now);
+        out.set("context_switches_per_sec", activity.context_switches);
+        out.set("interrupts_per_sec", activity.interrupts);
+        out.set("forks_per_sec", activity.forks);
+        out.set("minor_faults_per_sec", activity.minor_faults);
+        out.set("major_faults_per_sec", activity.major_faults);
+    }
+}
+
+// --- Processes -------------------------------------------------------------
+
+/// Heaviest processes by the configured sort key
+const TOP_PROCESS_METRICS: &[MetricDef] = &[MetricDef::new("top_processes", Unit::None)];
+
+struct TopProcessSource {
+    sys: System,
+    ranker: ProcessRanker,
+}
+
+impl TopProcessSource {
+    fn new() -> Self {
+        Self {
+            sys: system_with_cpu_list(),
+            ranker: ProcessRanker::new(),
+        }
+    }
+}
+
+impl MetricSource for TopProcessSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Processes
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        TOP_PROCESS_METRICS
+    }
+
+    fn reset(&mut self) {
+        // Also frees the process table until the metric is shown again
+        *self = Self::new();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // Walking the whole process table is the costliest refresh there is,
+        // which is why this only runs while the metric is shown
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory().with_disk_usage(),
+        );
+        let top = self.ranker.top(&self.sys, ctx.now, &ctx.cfg.processes);
+        out.set("top_processes", MetricValue::detail(&top));
+    }
+}
+
+/// The process named in `tracked_process`, while it is running
+const TRACKED_PROCESS_METRICS: &[MetricDef] = &[MetricDef::new("tracked_process", Unit::None)];
+
+struct TrackedProcessSource {
+    sys: System,
+    tracker: ProcessTracker,
+}
+
+impl TrackedProcessSource {
+    fn new() -> Self {
+        Self {
+            sys: system_with_cpu_list(),
+            tracker: ProcessTracker::new(),
+        }
+    }
+}
+
+impl MetricSource for TrackedProcessSource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Processes
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        TRACKED_PROCESS_METRICS
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn refresh(&mut self, ctx: &SourceContext, out: &mut SystemStats) {
+        // Tracked game/application — refreshes only its own PID once attached
+        let tracked = self.tracker.refresh(&mut self.sys, ctx.now, &ctx.cfg.tracked_process);
+        out.set("tracked_process", MetricValue::detail(&tracked));
+    }
+}
+
+// --- Battery ---------------------------------------------------------------
+
+/// System battery (all missing without one); power is negative while discharging
+const BATTERY_METRICS: &[MetricDef] = &[
+    MetricDef::new("battery_percent", Unit::Percent),
+    MetricDef::new("battery_power_w", Unit::Watts),
+    MetricDef::new("on_ac_power", Unit::None),
+    MetricDef::new("battery_minutes_remaining", Unit::Minutes),
+    MetricDef::new("peripheral_batteries", Unit::None),
+];
+
+struct BatterySource(BatteryMonitor);
+
+impl MetricSource for BatterySource {
+    fn group(&self) -> MetricGroup {
+        MetricGroup::Battery
+    }
+
+    fn metrics(&self) -> &'static [MetricDef] {
+        BATTERY_METRICS
+    }
+
+    fn refresh(&mut self, _ctx: &SourceContext, out: &mut SystemStats) {
+        // Laptop battery and wireless peripherals
+        let battery = self.0.refresh();
+        out.set("battery_percent", battery.percent);
+        out.set("battery_power_w", battery.power_w);
+        out.set("on_ac_power", battery.on_ac);
+        out.set("battery_minutes_remaining", battery.minutes_remaining);
+        out.set("peripheral_batteries", MetricValue::detail(&battery.peripherals));
+    }
+}